@@ -0,0 +1,62 @@
+use std::{fmt::Write, fs, path::Path};
+
+use common::{Bench, Error, Input};
+use serde_json::json;
+
+use crate::{days::Day, output::nanos};
+
+/// The timings of every phase as a table.
+pub fn render(day: &Day, bench: &Bench) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Day {:02} - {} runs", day.number, bench.runs);
+    let _ = writeln!(out, "{:<8} {:>12} {:>12} {:>12}", "phase", "min", "median", "p95");
+    for phase in &bench.phases {
+        let _ = writeln!(
+            out,
+            "{:<8} {:>12} {:>12} {:>12}",
+            phase.phase.to_string(),
+            format!("{:?}", phase.stats.min),
+            format!("{:?}", phase.stats.median),
+            format!("{:?}", phase.stats.p95)
+        );
+    }
+    out
+}
+
+/// Writes the timings to `path` as JSON, with durations in nanoseconds.
+pub fn write_json(path: &Path, day: &Day, input: &Input, bench: &Bench) -> Result<(), Error> {
+    let phases: Vec<_> = bench
+        .phases
+        .iter()
+        .map(|phase| {
+            json!({
+                "phase": phase.phase.to_string(),
+                "min_ns": nanos(phase.stats.min),
+                "median_ns": nanos(phase.stats.median),
+                "p95_ns": nanos(phase.stats.p95),
+            })
+        })
+        .collect();
+    let answers: Vec<_> = bench
+        .answers
+        .iter()
+        .map(|answer| json!({ "part": answer.part.number(), "answer": answer.value }))
+        .collect();
+    let report = json!({
+        "day": day.number,
+        "input": input.to_string(),
+        "runs": bench.runs,
+        "phases": phases,
+        "answers": answers,
+    });
+
+    let write = || -> Result<(), Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(&report).map_err(|e| Error::other(e.to_string()).with_source(e))?;
+        fs::write(path, text + "\n")?;
+        Ok(())
+    };
+    write().map_err(|e| e.with_file(path.display()))
+}
@@ -0,0 +1,73 @@
+use common::{
+    anonymize, bench, generate,
+    memory::{profile, MemoryProfile},
+    solve, Answer, Bench, Error, Part,
+};
+
+/// Runs a query over an input and renders the result.
+pub type Query = fn(&str, &str) -> Result<String, Error>;
+
+/// Answers part two of an input the slow way the day's solution replaced.
+pub type BruteForce = fn(&str) -> Result<String, Error>;
+
+/// An entry of the runner's dispatch table.
+pub struct Day {
+    pub number: u8,
+    /// Directory of the day's crate relative to the workspace root; named inputs are looked up there.
+    pub dir: &'static str,
+    pub solve: fn(&str, &[Part]) -> Result<Vec<Answer>, Error>,
+    pub bench: fn(&str, &[Part], usize) -> Result<Bench, Error>,
+    /// Allocations of every phase; needs the counting allocator.
+    pub profile: fn(&str, &[Part]) -> Result<MemoryProfile, Error>,
+    /// Random input of the given seed and size.
+    pub generate: fn(u64, usize) -> Result<String, Error>,
+    /// Synthetic look-alike of an input, from a seed.
+    pub anonymize: fn(&str, u64) -> Result<String, Error>,
+    /// Runs a query over the input and renders the result, for days that have a query language.
+    pub query: Option<Query>,
+    /// Answers part two by brute force, for days that keep their original search.
+    pub brute_force: Option<BruteForce>,
+}
+
+/// Builds the dispatch table entry of a day crate, e.g. `day!(6, day06::Day06)`,
+/// `day!(2, day02::Day02, query = day02::query::run)` for a day with a query language, or
+/// `day!(5, day05::Day05, brute_force = day05::brute_force)` for a day with a brute force.
+macro_rules! day {
+    ($number:literal, $krate:ident :: $solution:ident) => {
+        day!($number, $krate::$solution, None, None)
+    };
+    ($number:literal, $krate:ident :: $solution:ident, query = $query:path) => {
+        day!($number, $krate::$solution, Some($query), None)
+    };
+    ($number:literal, $krate:ident :: $solution:ident, brute_force = $brute_force:path) => {
+        day!($number, $krate::$solution, None, Some($brute_force))
+    };
+    ($number:literal, $krate:ident :: $solution:ident, $query:expr, $brute_force:expr) => {
+        Day {
+            number: $number,
+            dir: stringify!($krate),
+            solve: solve::<$krate::$solution>,
+            bench: bench::<$krate::$solution>,
+            profile: profile::<$krate::$solution>,
+            generate: generate::<$krate::$solution>,
+            anonymize: anonymize::<$krate::$solution>,
+            query: $query,
+            brute_force: $brute_force,
+        }
+    };
+}
+
+pub const DAYS: &[Day] = &[
+    day!(2, day02::Day02, query = day02::query::run),
+    day!(3, day03::Day03),
+    day!(4, day04::Day04),
+    day!(5, day05::Day05, brute_force = day05::brute_force),
+    day!(6, day06::Day06),
+    day!(7, day07::Day07),
+];
+
+pub fn find(number: u8) -> Result<&'static Day, Error> {
+    DAYS.iter()
+        .find(|day| day.number == number)
+        .ok_or_else(|| Error::validation(format!("Day {:02} is not registered", number)))
+}
@@ -0,0 +1,82 @@
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use common::{
+    encryption::{self, Key, KEY_FILE},
+    Error, Input, InputLocator,
+};
+
+/// The key to encrypt with. If none is configured, a new one is written to `.advoc-key` in `root`
+/// and its path is returned as well, since losing it makes the encrypted inputs unreadable.
+pub fn key_or_generate(root: &Path) -> Result<(Key, Option<PathBuf>), Error> {
+    if let Some(key) = Key::lookup(root)? {
+        return Ok((key, None));
+    }
+    let key = Key::generate();
+    let path = root.join(KEY_FILE);
+    let write = || -> Result<(), Error> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        // Only the owner may read the key
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&path)?;
+        writeln!(file, "{}", key.to_hex())?;
+        Ok(())
+    };
+    write().map_err(|e| e.with_file(path.display()))?;
+    Ok((key, Some(path)))
+}
+
+fn input_path(locator: &InputLocator, spec: &str) -> Result<PathBuf, Error> {
+    match locator.locate(spec) {
+        Input::File(path) => Ok(path),
+        Input::Stdin => Err(Error::validation("Only files can be encrypted or decrypted")),
+    }
+}
+
+/// Writes `<input>.enc` for every input and removes the plain file unless `keep`. An input that
+/// is only present encrypted is skipped. Returns the files that were written.
+pub fn encrypt_inputs(key: &Key, locator: &InputLocator, inputs: &[String], keep: bool) -> Result<Vec<PathBuf>, Error> {
+    let mut written = Vec::new();
+    for spec in inputs {
+        let path = input_path(locator, spec)?;
+        let encrypted = encryption::encrypted_path(&path);
+        if !path.exists() && encrypted.exists() {
+            continue;
+        }
+        let plaintext = fs::read(&path).map_err(|e| Error::from(e).with_file(path.display()))?;
+        let ciphertext = encryption::encrypt(key, &plaintext);
+        fs::write(&encrypted, &ciphertext).map_err(|e| Error::from(e).with_file(encrypted.display()))?;
+        if !keep {
+            fs::remove_file(&path).map_err(|e| Error::from(e).with_file(path.display()))?;
+        }
+        written.push(encrypted);
+    }
+    Ok(written)
+}
+
+/// Writes the plain file of every encrypted input, keeping the encrypted one. A plain file that
+/// differs from the decrypted content is not overwritten. Returns the files that were written.
+pub fn decrypt_inputs(locator: &InputLocator, inputs: &[String]) -> Result<Vec<PathBuf>, Error> {
+    let mut written = Vec::new();
+    for spec in inputs {
+        let path = input_path(locator, spec)?;
+        let plaintext = encryption::read_encrypted(&encryption::encrypted_path(&path))?;
+        match fs::read_to_string(&path) {
+            Ok(existing) if existing == plaintext => continue,
+            Ok(_) => {
+                return Err(Error::validation("The plain input differs from the encrypted one; remove it first").with_file(path.display()))
+            }
+            Err(_) => {}
+        }
+        fs::write(&path, plaintext).map_err(|e| Error::from(e).with_file(path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
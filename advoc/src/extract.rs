@@ -0,0 +1,318 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use common::{Answers, Error, Part, ANSWERS_FILE};
+
+/// What a saved puzzle description says about its examples.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extracted {
+    /// Distinct example inputs in order of appearance, named `example`, `example2`, ...
+    pub examples: Vec<(String, String)>,
+    /// The expected answer of each part for the example it was stated for.
+    pub answers: Vec<(String, Part, String)>,
+}
+
+/// Extracts the examples and the expected answers from a puzzle description saved as HTML (by
+/// the browser) or markdown. The format is taken from the file extension.
+///
+/// The description is split into one section per part. The first code block of a section is
+/// its example, or the example of the previous part if the section has none, and the last
+/// emphasized code span is the expected answer.
+pub fn extract(path: &Path) -> Result<Extracted, Error> {
+    let text = fs::read_to_string(path).map_err(|e| Error::from(e).with_file(path.display()))?;
+    let markdown = path.extension().is_some_and(|ext| ext == "md" || ext == "markdown");
+    let sections = if markdown { markdown_sections(&text) } else { html_sections(&text) };
+    let extracted = combine(sections);
+    if extracted.examples.is_empty() {
+        return Err(Error::parse("No example code block was found").with_file(path.display()));
+    }
+    Ok(extracted)
+}
+
+/// The code blocks and the emphasized code spans of one part of the description.
+#[derive(Debug, Default)]
+struct Section {
+    blocks: Vec<String>,
+    emphasized: Vec<String>,
+}
+
+fn combine(sections: Vec<Section>) -> Extracted {
+    let mut extracted = Extracted::default();
+    let mut example: Option<String> = None;
+    for (section, part) in sections.into_iter().zip(Part::ALL) {
+        if let Some(block) = section.blocks.into_iter().next() {
+            let name = match extracted.examples.iter().find(|(_, data)| *data == block) {
+                Some((name, _)) => name.clone(),
+                None => {
+                    let name = match extracted.examples.len() {
+                        0 => "example".to_string(),
+                        n => format!("example{}", n + 1),
+                    };
+                    extracted.examples.push((name.clone(), block));
+                    name
+                }
+            };
+            example = Some(name);
+        }
+        if let (Some(example), Some(answer)) = (&example, section.emphasized.last()) {
+            extracted.answers.push((example.clone(), part, answer.clone()));
+        }
+    }
+    extracted
+}
+
+/// Every part of a puzzle page is an `<article>`; code blocks are `<pre><code>` and answers are
+/// `<code><em>`, sometimes written the other way round.
+fn html_sections(html: &str) -> Vec<Section> {
+    let articles: Vec<&str> = html.split("<article").skip(1).collect();
+    let articles = if articles.is_empty() { vec![html] } else { articles };
+    articles
+        .into_iter()
+        .map(|article| Section {
+            blocks: between(article, "<pre><code>", "</code></pre>")
+                .into_iter()
+                .map(|(_, block)| with_final_newline(html_text(block)))
+                .collect(),
+            emphasized: between(article, "<code><em>", "</em></code>")
+                .into_iter()
+                .chain(between(article, "<em><code>", "</code></em>"))
+                .map(|(offset, span)| (offset, html_text(span)))
+                .collect::<BTreeMap<_, _>>()
+                .into_values()
+                .collect(),
+        })
+        .collect()
+}
+
+/// Parts are separated by the `--- Part Two ---` heading; code blocks are fenced with backticks
+/// and answers are code spans wrapped in emphasis, like ``*`288`*`` or `` `**288**` ``.
+fn markdown_sections(markdown: &str) -> Vec<Section> {
+    let mut sections = vec![Section::default()];
+    let mut lines = markdown.lines();
+    while let Some(line) = lines.next() {
+        if line.contains("--- Part Two ---") {
+            sections.push(Section::default());
+        }
+        let section = sections.last_mut().expect("there is always a section");
+        if line.trim_start().starts_with("```") {
+            let block: Vec<&str> = lines.by_ref().take_while(|line| !line.trim_start().starts_with("```")).collect();
+            section.blocks.push(with_final_newline(block.join("\n")));
+        } else {
+            section.emphasized.extend(emphasized_code(line));
+        }
+    }
+    sections
+}
+
+/// Code spans of a markdown line that are also emphasized, in either nesting order.
+fn emphasized_code(line: &str) -> Vec<String> {
+    let is_marker = |c: char| matches!(c, '*' | '_' | '`');
+    let mut spans = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(is_marker) {
+        let after_open = &rest[start..];
+        let open_len = after_open.find(|c: char| !is_marker(c)).unwrap_or(after_open.len());
+        let open = &after_open[..open_len];
+        let body = &after_open[open_len..];
+        let Some(body_len) = body.find(is_marker) else {
+            break;
+        };
+        let close: String = open.chars().rev().collect();
+        let emphasized = open.contains('`') && open.contains(['*', '_']);
+        if emphasized && body_len > 0 && body[body_len..].starts_with(&close) {
+            spans.push(body[..body_len].to_string());
+            rest = &body[body_len + close.len()..];
+        } else {
+            rest = &after_open[open_len.max(1)..];
+        }
+    }
+    spans
+}
+
+/// The text between every `open` and the next `close`, with the offset where it starts.
+fn between<'a>(text: &'a str, open: &str, close: &str) -> Vec<(usize, &'a str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find(open) {
+        let start = offset + start + open.len();
+        let Some(len) = text[start..].find(close) else {
+            break;
+        };
+        found.push((start, &text[start..start + len]));
+        offset = start + len + close.len();
+    }
+    found
+}
+
+/// Drops the tags of an HTML fragment and decodes its character references.
+fn html_text(fragment: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in fragment.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&text)
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let c = entity.and_then(|(name, _)| match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => {
+                let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name.strip_prefix('#')?.parse().ok(),
+                };
+                code.and_then(char::from_u32)
+            }
+        });
+        match (c, entity) {
+            (Some(c), Some((_, end))) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded + rest
+}
+
+fn with_final_newline(mut text: String) -> String {
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+/// Writes the examples next to the day's inputs and merges the answers into its `answers.toml`.
+///
+/// Existing inputs and answers that differ are only replaced with `force`; everything is checked
+/// before the first file is written. Returns the files that were written.
+pub fn write(dir: &Path, extracted: &Extracted, force: bool) -> Result<Vec<PathBuf>, Error> {
+    let mut written = Vec::new();
+    let mut examples = Vec::new();
+    for (name, data) in &extracted.examples {
+        let path = dir.join(format!("{}.dat", name));
+        match fs::read_to_string(&path) {
+            Ok(existing) if existing == *data => continue,
+            Ok(_) if !force => {
+                return Err(
+                    Error::validation(format!("{} differs from the extracted example; use --force to replace it", name))
+                        .with_file(path.display()),
+                )
+            }
+            _ => examples.push((path, data)),
+        }
+    }
+
+    let mut answers = Answers::load(dir)?;
+    let mut changed = false;
+    for (input, part, answer) in &extracted.answers {
+        match answers.expected(input, *part) {
+            Some(existing) if existing == answer => continue,
+            Some(existing) if !force => {
+                return Err(Error::validation(format!(
+                    "answers.toml records {} for {} part {}, but the description says {}; use --force to replace it",
+                    existing, input, part, answer
+                )))
+            }
+            _ => changed = true,
+        }
+        answers.insert(input, *part, answer.clone());
+    }
+
+    for (path, data) in examples {
+        fs::write(&path, data).map_err(|e| Error::from(e).with_file(path.display()))?;
+        written.push(path);
+    }
+    if changed {
+        answers.save(dir)?;
+        written.push(dir.join(ANSWERS_FILE));
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 6: Wait For It ---</h2>
+<p>For example:</p>
+<pre><code>Time:      7  15   30
+Distance:  9  40  200
+</code></pre>
+<p>The first race lasts <code>7</code> milliseconds, you could hold the button for <em>4</em> ways.</p>
+<p>In this example, multiplying these values together produces <code><em>288</em></code> (<code>4</code> * <code>8</code> * <code>9</code>).</p>
+</article>
+<p>Your puzzle answer was <code>503424</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>So, the example from before:</p>
+<pre><code>Time:      7  15   30
+Distance:  9  40  200
+</code></pre>
+<p>...now instead means <code>Time: 71530</code>. You could win this race in <code><em>71503</em></code> ways.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_extract_html() {
+        let extracted = combine(html_sections(HTML));
+        assert_eq!(
+            extracted.examples,
+            vec![("example".to_string(), "Time:      7  15   30\nDistance:  9  40  200\n".to_string())]
+        );
+        assert_eq!(
+            extracted.answers,
+            vec![
+                ("example".to_string(), Part::One, "288".to_string()),
+                ("example".to_string(), Part::Two, "71503".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_markdown() {
+        let markdown = "## --- Day 3: Gear Ratios ---\n\n```\n467..114..\n...*......\n```\n\nThe sum is *`4361`*.\n\n\
+                        ## --- Part Two ---\n\n```\n467..114..\n```\n\nAdding up all of the gear ratios produces `**467835**`.\n";
+        let extracted = combine(markdown_sections(markdown));
+        assert_eq!(extracted.examples.len(), 2);
+        assert_eq!(extracted.examples[1], ("example2".to_string(), "467..114..\n".to_string()));
+        assert_eq!(
+            extracted.answers,
+            vec![
+                ("example".to_string(), Part::One, "4361".to_string()),
+                ("example2".to_string(), Part::Two, "467835".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html_text() {
+        assert_eq!(
+            html_text("a &lt;<em>b</em>&gt; &amp;&#65;&#x42; &unknown; &"),
+            "a <b> &AB &unknown; &"
+        );
+        assert_eq!(emphasized_code("*not code* and `plain` and _`42`_"), vec!["42"]);
+    }
+}
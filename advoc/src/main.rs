@@ -0,0 +1,421 @@
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use clap::{Args, Parser, Subcommand};
+use common::{memory::CountingAllocator, telemetry::Telemetry, Answer, Error, InputLocator, Part, DEFAULT_INPUT};
+use output::{Format, Record};
+use tracing::{info, info_span};
+
+mod bench;
+mod days;
+mod encrypt;
+mod extract;
+mod memory;
+mod output;
+mod perf;
+mod scaffold;
+mod verify;
+mod watch;
+
+/// Only counts once `run --alloc` enables it.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Debug, Parser)]
+#[command(name = "advoc", about = "Runs the Advent of Code 2023 puzzles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Log filter, e.g. `debug` or `day05=trace` [default: $RUST_LOG or info]
+    #[arg(long, global = true, value_name = "FILTER")]
+    log: Option<String>,
+
+    /// Write logs as JSON lines
+    #[arg(long, global = true)]
+    log_json: bool,
+
+    /// Log the busy and idle time of every phase
+    #[arg(long, global = true)]
+    log_spans: bool,
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// Number of the day, e.g. 6
+    day: u8,
+
+    /// Only run this part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Input name (e.g. example), file path or `-` for stdin [default: $ADVOC_INPUT or testdata]
+    #[arg(long)]
+    input: Option<String>,
+
+    /// How to print the answers
+    #[arg(long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    /// Parse and run each part N times and report min/median/p95 per phase
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+
+    /// Where to write the benchmark results [default: target/bench/dayNN.json]
+    #[arg(long, value_name = "PATH", requires = "bench")]
+    bench_json: Option<PathBuf>,
+
+    /// Count allocations, allocated bytes and peak memory per phase
+    #[arg(long, conflicts_with = "bench")]
+    alloc: bool,
+
+    /// Answer a query over the input instead of the parts, e.g. "where max(red) > 10 select sum(id)"
+    #[arg(long, conflicts_with_all = ["part", "bench", "alloc"])]
+    query: Option<String>,
+
+    /// Answer part 2 by brute force, with progress bars and a checkpoint to resume from after Ctrl-C
+    #[arg(long, conflicts_with_all = ["part", "bench", "alloc", "query"])]
+    brute_force: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum PerfCommand {
+    /// Show the benchmark medians per commit and flag regressions
+    Report {
+        /// Only report this day
+        #[arg(long)]
+        day: Option<u8>,
+
+        /// Only report this input, e.g. testdata
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Flag a commit whose median is more than this many percent slower than the one before
+        #[arg(long, value_name = "PERCENT", default_value_t = perf::DEFAULT_THRESHOLD)]
+        threshold: f64,
+
+        /// The history to read [default: target/perf/history.jsonl]
+        #[arg(long, value_name = "PATH")]
+        history: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Solve one day's puzzle
+    Run(RunArgs),
+    /// Create and register the crate of a new day
+    New {
+        /// Number of the day, e.g. 8
+        day: u8,
+    },
+    /// Print a random input for a day
+    Generate {
+        /// Number of the day, e.g. 6
+        day: u8,
+
+        /// Seed of the random generator; the same seed gives the same input [default: random]
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Number of records (games, cards, races, ...) to generate
+        #[arg(long, default_value_t = 10)]
+        size: usize,
+    },
+    /// Write the examples and expected answers of a saved puzzle description to a day's inputs
+    Extract {
+        /// Number of the day, e.g. 6
+        day: u8,
+
+        /// The puzzle page saved as HTML, or as markdown (`.md`)
+        file: PathBuf,
+
+        /// Replace example inputs and answers that differ from the description
+        #[arg(long)]
+        force: bool,
+    },
+    /// Encrypt personal inputs to `<input>.enc` so they can be committed publicly
+    Encrypt {
+        /// Only encrypt the inputs of this day
+        day: Option<u8>,
+
+        /// Input name or file path; may be repeated
+        #[arg(long, default_value = DEFAULT_INPUT)]
+        input: Vec<String>,
+
+        /// Keep the plain inputs next to the encrypted ones
+        #[arg(long)]
+        keep: bool,
+    },
+    /// Restore the plain inputs from their encrypted copies
+    Decrypt {
+        /// Only decrypt the inputs of this day
+        day: Option<u8>,
+
+        /// Input name or file path; may be repeated
+        #[arg(long, default_value = DEFAULT_INPUT)]
+        input: Vec<String>,
+    },
+    /// Print a synthetic input with the shape and statistics of a real one
+    Anonymize {
+        /// Number of the day, e.g. 5
+        day: u8,
+
+        /// Input name (e.g. testdata), file path or `-` for stdin [default: $ADVOC_INPUT or testdata]
+        #[arg(long)]
+        input: Option<String>,
+
+        /// Seed of the random generator; the same seed gives the same input [default: random]
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Check the answers of every input against the day's answers.toml
+    Verify {
+        /// Only verify this day
+        day: Option<u8>,
+    },
+    /// Inspect the history of benchmark runs
+    Perf {
+        #[command(subcommand)]
+        command: PerfCommand,
+    },
+    /// Rebuild and rerun a day whenever its sources or inputs change
+    Watch {
+        /// Number of the day, e.g. 6
+        day: u8,
+
+        /// Only run this input; may be repeated [default: every input of the day]
+        #[arg(long)]
+        input: Vec<String>,
+
+        /// Build and run with optimizations
+        #[arg(long)]
+        release: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let telemetry = Telemetry::new()
+        .with_filter(cli.log.as_deref())
+        .with_json(cli.log_json)
+        .with_span_timing(cli.log_spans);
+    if let Err(e) = telemetry.init() {
+        eprint!("{}", e.render());
+        return ExitCode::FAILURE;
+    }
+
+    let result = match cli.command {
+        Command::Run(args) => run(args),
+        Command::New { day } => new_day(day),
+        Command::Generate { day, seed, size } => generate(day, seed, size),
+        Command::Anonymize { day, input, seed } => anonymize(day, input.as_deref(), seed),
+        Command::Extract { day, file, force } => extract(day, &file, force),
+        Command::Encrypt { day, input, keep } => encrypt(day, &input, keep),
+        Command::Decrypt { day, input } => decrypt(day, &input),
+        Command::Verify { day } => verify(day),
+        Command::Perf { command } => perf(command),
+        Command::Watch { day, input, release } => days::find(day).and_then(|day| watch::watch(day, &input, release)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprint!("{}", e.render());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: RunArgs) -> Result<(), Error> {
+    let day = days::find(args.day)?;
+    let _span = info_span!("day", day = day.number).entered();
+    let parts = match args.part {
+        Some(part) => vec![Part::try_from(part)?],
+        None => Part::ALL.to_vec(),
+    };
+    let locator = InputLocator::new(workspace_root().join(day.dir));
+    let input = locator.select(args.input.as_deref(), DEFAULT_INPUT);
+    let data = input.read()?;
+
+    if let Some(query) = args.query {
+        let run_query = day
+            .query
+            .ok_or_else(|| Error::validation(format!("Day {:02} has no query language", day.number)))?;
+        print!("{}", run_query(&data, &query).map_err(|e| e.with_file(&input))?);
+        return Ok(());
+    }
+
+    if args.brute_force {
+        let brute_force = day
+            .brute_force
+            .ok_or_else(|| Error::validation(format!("Day {:02} has no brute force", day.number)))?;
+        let start = Instant::now();
+        let value = brute_force(&data).map_err(|e| e.with_file(&input))?;
+        let answer = Answer {
+            part: Part::Two,
+            value,
+            duration: start.elapsed(),
+        };
+        print!("{}", output::render(args.format, &[Record::new(day.number, &input, answer)]));
+        return Ok(());
+    }
+
+    if let Some(runs) = args.bench {
+        let results = (day.bench)(&data, &parts, runs as usize).map_err(|e| e.with_file(&input))?;
+        let records: Vec<Record> = results
+            .answers
+            .iter()
+            .map(|answer| Record::new(day.number, &input, answer.clone()))
+            .collect();
+        print!("{}", output::render(args.format, &records));
+        output::report(args.format, &bench::render(day, &results));
+        let path = args
+            .bench_json
+            .unwrap_or_else(|| workspace_root().join("target/bench").join(format!("day{:02}.json", day.number)));
+        bench::write_json(&path, day, &input, &results)?;
+        output::report(args.format, &format!("Wrote {}\n", path.display()));
+        let history = perf::history_path();
+        perf::record(&history, day, &input, &results)?;
+        output::report(args.format, &format!("Appended to {}\n", history.display()));
+        return Ok(());
+    }
+
+    if args.alloc {
+        let profile = (day.profile)(&data, &parts).map_err(|e| e.with_file(&input))?;
+        let records: Vec<Record> = profile
+            .answers
+            .iter()
+            .map(|answer| Record::new(day.number, &input, answer.clone()))
+            .collect();
+        print!("{}", output::render(args.format, &records));
+        output::report(args.format, &memory::render(day, &profile));
+        return Ok(());
+    }
+
+    let answers = (day.solve)(&data, &parts).map_err(|e| e.with_file(&input))?;
+    let records: Vec<Record> = answers.into_iter().map(|answer| Record::new(day.number, &input, answer)).collect();
+    print!("{}", output::render(args.format, &records));
+    Ok(())
+}
+
+fn new_day(day: u8) -> Result<(), Error> {
+    for path in scaffold::new_day(workspace_root(), day)? {
+        println!("Wrote {}", path.display());
+    }
+    println!("Rebuild the runner to use day {}", day);
+    Ok(())
+}
+
+fn generate(day: u8, seed: Option<u64>, size: usize) -> Result<(), Error> {
+    let day = days::find(day)?;
+    let seed = seed.unwrap_or_else(random_seed);
+    info!(day = day.number, seed, size, "Generating input");
+    print!("{}", (day.generate)(seed, size)?);
+    Ok(())
+}
+
+fn anonymize(day: u8, input: Option<&str>, seed: Option<u64>) -> Result<(), Error> {
+    let day = days::find(day)?;
+    let input = InputLocator::new(workspace_root().join(day.dir)).select(input, DEFAULT_INPUT);
+    let seed = seed.unwrap_or_else(random_seed);
+    info!(day = day.number, %input, seed, "Anonymizing input");
+    print!("{}", (day.anonymize)(&input.read()?, seed).map_err(|e| e.with_file(&input))?);
+    Ok(())
+}
+
+fn random_seed() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    now.as_nanos() as u64
+}
+
+fn extract(day: u8, file: &Path, force: bool) -> Result<(), Error> {
+    let day = days::find(day)?;
+    let extracted = extract::extract(file)?;
+    for (input, part, answer) in &extracted.answers {
+        println!("{} part {}: {}", input, part, answer);
+    }
+    let written = extract::write(&workspace_root().join(day.dir), &extracted, force)?;
+    if written.is_empty() {
+        println!("The inputs and answers of day {:02} are up to date", day.number);
+    }
+    for path in written {
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn encrypt(day: Option<u8>, inputs: &[String], keep: bool) -> Result<(), Error> {
+    let (key, generated) = encrypt::key_or_generate(workspace_root())?;
+    if let Some(path) = generated {
+        println!(
+            "Generated a new key in {}; keep a copy, the inputs cannot be decrypted without it",
+            path.display()
+        );
+    }
+    for day in selected_days(day)? {
+        let locator = InputLocator::new(workspace_root().join(day.dir));
+        for path in encrypt::encrypt_inputs(&key, &locator, inputs, keep)? {
+            println!("Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn decrypt(day: Option<u8>, inputs: &[String]) -> Result<(), Error> {
+    for day in selected_days(day)? {
+        let locator = InputLocator::new(workspace_root().join(day.dir));
+        for path in encrypt::decrypt_inputs(&locator, inputs)? {
+            println!("Wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn verify(day: Option<u8>) -> Result<(), Error> {
+    let days = selected_days(day)?;
+    let checks = verify::verify(days)?;
+    verify::print(&checks);
+    match verify::failures(&checks) {
+        0 => Ok(()),
+        failures => Err(Error::validation(format!("{} of {} checks failed", failures, checks.len()))),
+    }
+}
+
+fn perf(command: PerfCommand) -> Result<(), Error> {
+    let PerfCommand::Report {
+        day,
+        input,
+        threshold,
+        history,
+    } = command;
+    let path = history.unwrap_or_else(perf::history_path);
+    let mut entries = perf::load(&path)?;
+    entries.retain(|entry| day.is_none_or(|day| entry.day == day) && input.as_ref().is_none_or(|input| entry.input == *input));
+    if entries.is_empty() {
+        println!("No benchmark runs recorded in {}; run a day with --bench first", path.display());
+        return Ok(());
+    }
+    let (report, regressions) = perf::report(&entries, threshold);
+    print!("{}", report);
+    match regressions.len() {
+        0 => Ok(()),
+        n => Err(Error::validation(format!("{} regressions beyond {}%", n, threshold))),
+    }
+}
+
+/// The given day, or every registered day.
+fn selected_days(day: Option<u8>) -> Result<&'static [days::Day], Error> {
+    match day {
+        Some(day) => Ok(std::slice::from_ref(days::find(day)?)),
+        None => Ok(days::DAYS),
+    }
+}
+
+fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("advoc lives inside the workspace")
+}
@@ -0,0 +1,53 @@
+use std::fmt::Write;
+
+use common::memory::MemoryProfile;
+
+use crate::days::Day;
+
+/// The allocations of every phase as a table.
+pub fn render(day: &Day, profile: &MemoryProfile) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Day {:02} - allocations", day.number);
+    let _ = writeln!(out, "{:<8} {:>12} {:>12} {:>12}", "phase", "allocations", "bytes", "peak");
+    for phase in &profile.phases {
+        let _ = writeln!(
+            out,
+            "{:<8} {:>12} {:>12} {:>12}",
+            phase.phase.to_string(),
+            phase.usage.allocations,
+            bytes(phase.usage.bytes),
+            bytes(phase.usage.peak)
+        );
+    }
+    out
+}
+
+/// A byte count in binary units, e.g. `1.5 MiB`.
+fn bytes(count: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if count < 1024 {
+        return format!("{} B", count);
+    }
+    let mut scaled = count as f64;
+    let mut unit = "";
+    for next in UNITS {
+        if scaled < 1024.0 {
+            break;
+        }
+        scaled /= 1024.0;
+        unit = next;
+    }
+    format!("{:.1} {}", scaled, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(bytes(1023), "1023 B");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(3 << 30), "3.0 GiB");
+    }
+}
@@ -0,0 +1,159 @@
+use std::{fmt::Write, time::Duration};
+
+use clap::ValueEnum;
+use common::{Answer, Input};
+use serde_json::json;
+
+/// How the runner prints answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Aligned columns for humans
+    Table,
+    /// One JSON object per line
+    Json,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+/// One answer together with where it came from.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+    pub duration: Duration,
+}
+
+impl Record {
+    pub fn new(day: u8, input: &Input, answer: Answer) -> Self {
+        Self {
+            day,
+            part: answer.part.number(),
+            input: input.name(),
+            answer: answer.value,
+            duration: answer.duration,
+        }
+    }
+}
+
+/// Prints a report for humans: after a table of answers on stdout, or on stderr when stdout
+/// carries json or csv records, so that it stays machine readable.
+pub fn report(format: Format, text: &str) {
+    match format {
+        Format::Table => print!("{}", text),
+        Format::Json | Format::Csv => eprint!("{}", text),
+    }
+}
+
+const COLUMNS: [&str; 5] = ["day", "part", "input", "answer", "duration_ns"];
+
+pub fn render(format: Format, records: &[Record]) -> String {
+    match format {
+        Format::Table => table(records),
+        Format::Json => records
+            .iter()
+            .map(|record| {
+                json!({
+                    "day": record.day,
+                    "part": record.part,
+                    "input": record.input,
+                    "answer": record.answer,
+                    "duration_ns": nanos(record.duration),
+                })
+                .to_string()
+                    + "\n"
+            })
+            .collect(),
+        Format::Csv => {
+            let mut out = COLUMNS.join(",") + "\n";
+            for record in records {
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{}",
+                    record.day,
+                    record.part,
+                    csv_field(&record.input),
+                    csv_field(&record.answer),
+                    nanos(record.duration)
+                );
+            }
+            out
+        }
+    }
+}
+
+fn table(records: &[Record]) -> String {
+    let input_width = records
+        .iter()
+        .map(|record| record.input.len())
+        .chain(["input".len()])
+        .max()
+        .unwrap_or_default();
+    let answer_width = records
+        .iter()
+        .map(|record| record.answer.len())
+        .chain(["answer".len()])
+        .max()
+        .unwrap_or_default();
+    let mut out = format!(
+        "{:<4} {:<4} {:<input_width$} {:<answer_width$} duration\n",
+        "day", "part", "input", "answer"
+    );
+    for record in records {
+        let _ = writeln!(
+            out,
+            "{:<4} {:<4} {:<input_width$} {:<answer_width$} {:?}",
+            format!("{:02}", record.day),
+            record.part,
+            record.input,
+            record.answer,
+            record.duration
+        );
+    }
+    out
+}
+
+/// Quotes a field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(answer: &str) -> Record {
+        Record {
+            day: 6,
+            part: 1,
+            input: "example".to_string(),
+            answer: answer.to_string(),
+            duration: Duration::from_micros(3),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(Format::Json, &[record("288")]),
+            "{\"answer\":\"288\",\"day\":6,\"duration_ns\":3000,\"input\":\"example\",\"part\":1}\n"
+        );
+        assert_eq!(
+            render(Format::Csv, &[record("a,\"b\"")]),
+            "day,part,input,answer,duration_ns\n6,1,example,\"a,\"\"b\"\"\",3000\n"
+        );
+        assert_eq!(
+            render(Format::Table, &[record("288")]),
+            "day  part input   answer duration\n06   1    example 288    3µs\n"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::{self, OpenOptions},
+    io::Write as _,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use common::{Bench, Error, Input};
+use serde_json::{json, Value};
+
+use crate::{days::Day, output::nanos, workspace_root};
+
+/// Regressions smaller than this percentage are noise unless `--threshold` says otherwise.
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// The history of every benchmark run, one JSON object per phase and run.
+pub fn history_path() -> PathBuf {
+    workspace_root().join("target/perf/history.jsonl")
+}
+
+/// One benchmarked phase of one `advoc run --bench`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// Short hash of `HEAD`, with a `+` if the working tree had changes.
+    pub commit: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub day: u8,
+    pub input: String,
+    /// `parse`, `part 1` or `part 2`.
+    pub phase: String,
+    pub runs: usize,
+    pub median: Duration,
+    pub min: Duration,
+}
+
+/// The current commit, or `unknown` outside of a git checkout.
+pub fn current_commit() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(workspace_root())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit) => {
+            let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|status| !status.is_empty());
+            if dirty {
+                commit + "+"
+            } else {
+                commit
+            }
+        }
+        None => "unknown".to_string(),
+    }
+}
+
+/// Appends the phases of `bench` to the history at `path`.
+pub fn record(path: &Path, day: &Day, input: &Input, bench: &Bench) -> Result<(), Error> {
+    let commit = current_commit();
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let mut lines = String::new();
+    for phase in &bench.phases {
+        let entry = json!({
+            "commit": commit,
+            "timestamp": timestamp,
+            "day": day.number,
+            "input": input.name(),
+            "phase": phase.phase.to_string(),
+            "runs": bench.runs,
+            "median_ns": nanos(phase.stats.median),
+            "min_ns": nanos(phase.stats.min),
+        });
+        let _ = writeln!(lines, "{}", entry);
+    }
+
+    let append = || -> Result<(), Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(lines.as_bytes())?;
+        Ok(())
+    };
+    append().map_err(|e| e.with_file(path.display()))
+}
+
+/// Reads the history at `path`; a missing file is an empty history.
+pub fn load(path: &Path) -> Result<Vec<Entry>, Error> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(path).map_err(|e| Error::from(e).with_file(path.display()))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_entry(line).map_err(|e| e.with_line(i + 1, line).with_file(path.display())))
+        .collect()
+}
+
+fn parse_entry(line: &str) -> Result<Entry, Error> {
+    let value: Value = serde_json::from_str(line).map_err(|e| Error::parse(e.to_string()).with_source(e))?;
+    let text = |key: &str| {
+        value[key]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::parse(format!("Expected the string '{}'", key)))
+    };
+    let number = |key: &str| {
+        value[key]
+            .as_u64()
+            .ok_or_else(|| Error::parse(format!("Expected the number '{}'", key)))
+    };
+    Ok(Entry {
+        commit: text("commit")?,
+        timestamp: number("timestamp")?,
+        day: u8::try_from(number("day")?).map_err(|_| Error::parse("The day is out of range"))?,
+        input: text("input")?,
+        phase: text("phase")?,
+        runs: number("runs")? as usize,
+        median: Duration::from_nanos(number("median_ns")?),
+        min: Duration::from_nanos(number("min_ns")?),
+    })
+}
+
+/// A change of the median between two consecutive commits of one day, input and phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub input: String,
+    pub phase: String,
+    pub from: String,
+    pub to: String,
+    /// How much slower, in percent.
+    pub percent: f64,
+}
+
+/// Day, input and phase.
+type Series<'a> = (u8, &'a str, &'a str);
+
+/// One table per day, input and phase with the best median of every commit in the order they were
+/// first benchmarked, and the change against the commit before. Changes above `threshold` percent
+/// are flagged and returned.
+pub fn report(entries: &[Entry], threshold: f64) -> (String, Vec<Regression>) {
+    let mut series: BTreeMap<Series, Vec<(&str, Duration)>> = BTreeMap::new();
+    for entry in entries {
+        let commits = series.entry((entry.day, &entry.input, &entry.phase)).or_default();
+        match commits.iter_mut().find(|(commit, _)| *commit == entry.commit) {
+            Some((_, best)) => *best = (*best).min(entry.median),
+            None => commits.push((&entry.commit, entry.median)),
+        }
+    }
+
+    let mut out = String::new();
+    let mut regressions = Vec::new();
+    for ((day, input, phase), commits) in series {
+        let _ = writeln!(out, "Day {:02} {} {}", day, input, phase);
+        let mut previous: Option<(&str, Duration)> = None;
+        for (commit, median) in commits {
+            let mut line = format!("  {:<10} {:>14}", commit, format!("{:?}", median));
+            if let Some((from, before)) = previous {
+                let percent = (median.as_secs_f64() / before.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0) * 100.0;
+                let _ = write!(line, " {:>+8.1}%", percent);
+                if percent > threshold {
+                    line += "  REGRESSION";
+                    regressions.push(Regression {
+                        day,
+                        input: input.to_string(),
+                        phase: phase.to_string(),
+                        from: from.to_string(),
+                        to: commit.to_string(),
+                        percent,
+                    });
+                }
+            }
+            let _ = writeln!(out, "{}", line);
+            previous = Some((commit, median));
+        }
+    }
+    (out, regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(commit: &str, phase: &str, median_ms: u64) -> Entry {
+        Entry {
+            commit: commit.to_string(),
+            timestamp: 0,
+            day: 5,
+            input: "testdata".to_string(),
+            phase: phase.to_string(),
+            runs: 3,
+            median: Duration::from_millis(median_ms),
+            min: Duration::from_millis(median_ms),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let entries = [
+            entry("a1", "part 2", 100),
+            entry("a1", "part 2", 90),
+            entry("b2", "part 2", 50),
+            entry("c3+", "part 2", 60),
+            entry("a1", "parse", 1),
+        ];
+        let (text, regressions) = report(&entries, 10.0);
+        assert_eq!(
+            text,
+            "Day 05 testdata parse\n  a1                    1ms\n\
+             Day 05 testdata part 2\n  a1                   90ms\n  b2                   50ms    -44.4%\n  c3+                  60ms    +20.0%  REGRESSION\n"
+        );
+        assert_eq!(regressions.len(), 1);
+        assert_eq!((regressions[0].from.as_str(), regressions[0].to.as_str()), ("b2", "c3+"));
+        assert!(report(&entries, 25.0).1.is_empty());
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let line = r#"{"commit":"a1","day":5,"input":"testdata","median_ns":2000,"min_ns":1000,"phase":"part 2","runs":3,"timestamp":7}"#;
+        let entry = parse_entry(line).unwrap();
+        assert_eq!(entry.median, Duration::from_micros(2));
+        assert_eq!(entry.phase, "part 2");
+        assert!(parse_entry(r#"{"commit":"a1"}"#).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use common::Error;
+
+const CARGO_TOML: &str = include_str!("../templates/Cargo.toml.tmpl");
+const LIB_RS: &str = include_str!("../templates/lib.rs.tmpl");
+const GENERATE_RS: &str = include_str!("../templates/generate.rs.tmpl");
+const ANSWERS_TOML: &str = include_str!("../templates/answers.toml.tmpl");
+
+/// Creates the crate of a new day under `root` and registers it in the workspace members, the
+/// runner's dependencies and its dispatch table. Returns the files that were written.
+///
+/// Everything is checked before the first file is touched, so a refused day leaves no traces.
+pub fn new_day(root: &Path, number: u8) -> Result<Vec<PathBuf>, Error> {
+    if !(1..=25).contains(&number) {
+        return Err(Error::validation(format!("There is no day {} in Advent of Code", number)));
+    }
+    let krate = format!("day{:02}", number);
+    let dir = root.join(&krate);
+    if dir.exists() {
+        return Err(Error::validation(format!("{} already exists", dir.display())));
+    }
+
+    let workspace_manifest = root.join("Cargo.toml");
+    let runner_manifest = root.join("advoc/Cargo.toml");
+    let dispatch_table = root.join("advoc/src/days.rs");
+    let edits = [
+        (&workspace_manifest, add_member(&read(&workspace_manifest)?, &krate)),
+        (&runner_manifest, add_dependency(&read(&runner_manifest)?, &krate)),
+        (&dispatch_table, add_dispatch_entry(&read(&dispatch_table)?, number)),
+    ];
+    let mut edited = Vec::new();
+    for (path, edit) in edits {
+        edited.push((path, edit.map_err(|e| e.with_file(path.display()))?));
+    }
+
+    let render = |template: &str| {
+        template
+            .replace("{crate}", &krate)
+            .replace("{solution}", &format!("Day{:02}", number))
+            .replace("{nn}", &format!("{:02}", number))
+    };
+    let files = [
+        (dir.join("Cargo.toml"), render(CARGO_TOML)),
+        (dir.join("src/lib.rs"), render(LIB_RS)),
+        (dir.join("src/generate.rs"), render(GENERATE_RS)),
+        (dir.join("answers.toml"), ANSWERS_TOML.to_string()),
+        (dir.join("example.dat"), String::new()),
+        (dir.join("testdata.dat"), String::new()),
+    ];
+
+    let mut written = Vec::new();
+    fs::create_dir_all(dir.join("src")).map_err(|e| Error::from(e).with_file(dir.display()))?;
+    for (path, text) in files.into_iter().chain(edited.into_iter().map(|(path, text)| (path.clone(), text))) {
+        fs::write(&path, text).map_err(|e| Error::from(e).with_file(path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|e| Error::from(e).with_file(path.display()))
+}
+
+/// Adds `member` to the single-line `members = [...]` list of the workspace manifest, keeping it sorted.
+fn add_member(manifest: &str, member: &str) -> Result<String, Error> {
+    const MEMBERS: &str = "members = [";
+    let start = manifest
+        .find(MEMBERS)
+        .ok_or_else(|| Error::parse("The workspace manifest has no members list"))?
+        + MEMBERS.len();
+    let end = start
+        + manifest[start..]
+            .find(']')
+            .ok_or_else(|| Error::parse("The members list of the workspace manifest is not closed"))?;
+
+    let mut members: Vec<&str> = manifest[start..end]
+        .split(',')
+        .map(|member| member.trim().trim_matches('"'))
+        .filter(|member| !member.is_empty())
+        .collect();
+    if members.contains(&member) {
+        return Err(Error::validation(format!("{} is already a workspace member", member)));
+    }
+    members.push(member);
+    members.sort_unstable();
+    let members: Vec<String> = members.iter().map(|member| format!("\"{}\"", member)).collect();
+    Ok(format!("{} {}{}", &manifest[..start], members.join(", "), &manifest[end..]))
+}
+
+/// Adds a path dependency on `krate` to the `[dependencies]` of the runner, next to the other days.
+fn add_dependency(manifest: &str, krate: &str) -> Result<String, Error> {
+    let mut lines: Vec<&str> = manifest.lines().collect();
+    let section = lines
+        .iter()
+        .position(|line| line.trim() == "[dependencies]")
+        .ok_or_else(|| Error::parse("The runner's manifest has no [dependencies] section"))?;
+    let section_end = lines[section + 1..]
+        .iter()
+        .position(|line| line.trim().is_empty() || line.starts_with('['))
+        .map_or(lines.len(), |i| section + 1 + i);
+
+    let name = |line: &str| line.split('=').next().unwrap_or_default().trim().to_string();
+    if lines[section + 1..section_end].iter().any(|line| name(line) == krate) {
+        return Err(Error::validation(format!("The runner already depends on {}", krate)));
+    }
+    let days: Vec<usize> = (section + 1..section_end).filter(|i| name(lines[*i]).starts_with("day")).collect();
+    let position = match days.iter().find(|i| name(lines[**i]).as_str() > krate) {
+        Some(i) => *i,
+        None => days.last().map_or(section_end, |i| i + 1),
+    };
+    let dependency = format!("{} = {{ path = \"../{}\"}}", krate, krate);
+    lines.insert(position, &dependency);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds a `day!(..)` line for day `number` to the runner's dispatch table, ordered by day.
+fn add_dispatch_entry(days_rs: &str, number: u8) -> Result<String, Error> {
+    let mut lines: Vec<&str> = days_rs.lines().collect();
+    let table = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS"))
+        .ok_or_else(|| Error::parse("The dispatch table DAYS was not found"))?;
+    let table_end = table
+        + lines[table..]
+            .iter()
+            .position(|line| *line == "];")
+            .ok_or_else(|| Error::parse("The dispatch table DAYS is not closed"))?;
+
+    let entry_number = |line: &str| -> Option<u8> { line.trim().strip_prefix("day!(")?.split(',').next()?.trim().parse().ok() };
+    if lines[table..table_end].iter().any(|line| entry_number(line) == Some(number)) {
+        return Err(Error::validation(format!("Day {:02} is already registered", number)));
+    }
+    let position = lines[table..table_end]
+        .iter()
+        .position(|line| entry_number(line).is_some_and(|n| n > number))
+        .map_or(table_end, |i| table + i);
+    let entry = format!("    day!({}, day{:02}::Day{:02}),", number, number, number);
+    lines.insert(position, &entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_member() {
+        let manifest = "[workspace]\nresolver = \"2\"\nmembers = [ \"advoc\", \"common\", \"day02\", \"day07\"]\n";
+        assert_eq!(
+            add_member(manifest, "day05").unwrap(),
+            "[workspace]\nresolver = \"2\"\nmembers = [ \"advoc\", \"common\", \"day02\", \"day05\", \"day07\"]\n"
+        );
+        assert!(add_member(manifest, "day07").is_err());
+    }
+
+    #[test]
+    fn test_add_dependency() {
+        let manifest = "[package]\nname = \"advoc\"\n\n[dependencies]\nclap = \"4.5\"\nday02 = { path = \"../day02\"}\nday07 = { path = \"../day07\"}\ntracing = \"0.1\"\n";
+        let edited = add_dependency(manifest, "day05").unwrap();
+        assert!(edited.contains("day02 = { path = \"../day02\"}\nday05 = { path = \"../day05\"}\nday07"));
+        let edited = add_dependency(manifest, "day09").unwrap();
+        assert!(edited.contains("day07 = { path = \"../day07\"}\nday09 = { path = \"../day09\"}\ntracing"));
+        assert!(add_dependency(manifest, "day02").is_err());
+    }
+
+    #[test]
+    fn test_add_dispatch_entry() {
+        let days_rs = "pub const DAYS: &[Day] = &[\n    day!(2, day02::Day02),\n    day!(10, day10::Day10),\n];\n";
+        assert_eq!(
+            add_dispatch_entry(days_rs, 8).unwrap(),
+            "pub const DAYS: &[Day] = &[\n    day!(2, day02::Day02),\n    day!(8, day08::Day08),\n    day!(10, day10::Day10),\n];\n"
+        );
+        assert!(add_dispatch_entry(days_rs, 10).is_err());
+    }
+}
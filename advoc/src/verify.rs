@@ -0,0 +1,149 @@
+use std::{collections::BTreeSet, fmt::Display, fs};
+
+use common::{Answers, Error, InputLocator, Part};
+use tracing::info_span;
+
+use crate::{days::Day, workspace_root};
+
+/// Outcome of checking one part of one input against `answers.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// No expected answer is recorded; the part is not run.
+    Missing,
+    /// The input could not be read or the solution returned an error.
+    Error,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Missing => "missing",
+            Status::Error => "error",
+        };
+        f.pad(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub day: u8,
+    pub input: String,
+    pub part: Part,
+    pub expected: Option<String>,
+    /// The answer, or the error message if solving failed.
+    pub actual: Option<String>,
+    pub status: Status,
+}
+
+/// Runs every input of `days` that is on disk or listed in `answers.toml` and compares the answers.
+pub fn verify(days: &[Day]) -> Result<Vec<Check>, Error> {
+    let mut checks = Vec::new();
+    for day in days {
+        let locator = InputLocator::new(workspace_root().join(day.dir));
+        let answers = Answers::load(locator.dir())?;
+        for input in input_names(&locator, &answers)? {
+            checks.extend(verify_input(day, &locator, &answers, &input));
+        }
+    }
+    Ok(checks)
+}
+
+fn verify_input(day: &Day, locator: &InputLocator, answers: &Answers, input: &str) -> Vec<Check> {
+    let _span = info_span!("day", day = day.number, input).entered();
+    let check = |part: Part, actual: Option<String>, status: Status| Check {
+        day: day.number,
+        input: input.to_string(),
+        part,
+        expected: answers.expected(input, part).map(str::to_string),
+        actual,
+        status,
+    };
+    let (recorded, missing): (Vec<Part>, Vec<Part>) = Part::ALL.iter().partition(|part| answers.expected(input, **part).is_some());
+    let mut checks: Vec<Check> = missing.into_iter().map(|part| check(part, None, Status::Missing)).collect();
+    if recorded.is_empty() {
+        return checks;
+    }
+
+    let solved = locator.read(input).and_then(|data| (day.solve)(&data, &recorded));
+    match solved {
+        Ok(solved) => checks.extend(solved.into_iter().map(|answer| {
+            let status = if answers.expected(input, answer.part) == Some(answer.value.as_str()) {
+                Status::Pass
+            } else {
+                Status::Fail
+            };
+            check(answer.part, Some(answer.value), status)
+        })),
+        Err(e) => checks.extend(
+            recorded
+                .into_iter()
+                .map(|part| check(part, Some(e.message().to_string()), Status::Error)),
+        ),
+    }
+    checks.sort_by_key(|check| check.part);
+    checks
+}
+
+/// The `.dat` files (plain or encrypted) in the day's directory together with the inputs named in its answers file.
+pub fn input_names(locator: &InputLocator, answers: &Answers) -> Result<BTreeSet<String>, Error> {
+    let mut names: BTreeSet<String> = answers.inputs().map(str::to_string).collect();
+    for entry in fs::read_dir(locator.dir()).map_err(|e| Error::from(e).with_file(locator.dir().display()))? {
+        let path = entry?.path();
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        if let Some(name) = file_name.strip_suffix(".dat").or_else(|| file_name.strip_suffix(".dat.enc")) {
+            names.insert(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// Prints the checks as a table followed by a summary line.
+pub fn print(checks: &[Check]) {
+    let input_width = checks
+        .iter()
+        .map(|check| check.input.len())
+        .chain(["input".len()])
+        .max()
+        .unwrap_or_default();
+    let expected_width = checks
+        .iter()
+        .filter_map(|check| check.expected.as_ref().map(String::len))
+        .chain(["expected".len()])
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:<4} {:<input_width$} {:<4} {:<expected_width$} {:<7} actual",
+        "day", "input", "part", "expected", "status"
+    );
+    for check in checks {
+        println!(
+            "{:<4} {:<input_width$} {:<4} {:<expected_width$} {:<7} {}",
+            format!("{:02}", check.day),
+            check.input,
+            check.part.number(),
+            check.expected.as_deref().unwrap_or("-"),
+            check.status,
+            check.actual.as_deref().unwrap_or("-"),
+        );
+    }
+    let count = |status: Status| checks.iter().filter(|check| check.status == status).count();
+    println!(
+        "{} passed, {} failed, {} missing, {} errors",
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Missing),
+        count(Status::Error)
+    );
+}
+
+/// The number of checks that failed or errored; missing answers do not count as failures.
+pub fn failures(checks: &[Check]) -> usize {
+    checks
+        .iter()
+        .filter(|check| matches!(check.status, Status::Fail | Status::Error))
+        .count()
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    ffi::OsStr,
+    fmt::Write,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use common::{Answers, Error, InputLocator, Part};
+use notify::{
+    event::{AccessKind, AccessMode, ModifyKind},
+    Event, EventKind, RecursiveMode, Watcher,
+};
+use serde_json::Value;
+use tracing::{debug, info};
+
+use crate::{days::Day, verify::input_names, workspace_root};
+
+/// Changes arriving this soon after the first one are handled by the same rerun, so that an
+/// editor saving in several steps or a `git checkout` trigger only one build.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Only changes to these kinds of files trigger a rerun; editor swap and backup files do not.
+const WATCHED_EXTENSIONS: [&str; 4] = ["rs", "dat", "enc", "toml"];
+
+/// The answer of one part of one input, or the error the run reported.
+pub type Outcome = Result<String, String>;
+
+/// Outcomes of one run, keyed by input name and part.
+pub type Results = BTreeMap<(String, Part), Outcome>;
+
+/// Rebuilds the runner and reruns `inputs` of `day` (every input when empty) whenever a source
+/// file of the day's crate or one of its inputs changes. Runs until interrupted.
+///
+/// The running process cannot load its own rebuilt code, so every run is a child `advoc run`.
+pub fn watch(day: &Day, inputs: &[String], release: bool) -> Result<(), Error> {
+    let dir = workspace_root().join(day.dir);
+    let (sender, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(|e| Error::io("Could not start watching files").with_source(e))?;
+    // The day's directory for its inputs, manifest and answers, and everything below `src/`,
+    // including modules added later
+    for (path, mode) in [
+        (dir.clone(), RecursiveMode::NonRecursive),
+        (dir.join("src"), RecursiveMode::Recursive),
+    ] {
+        watcher
+            .watch(&path, mode)
+            .map_err(|e| Error::io("Could not watch").with_file(path.display()).with_source(e))?;
+    }
+    let mut previous = Results::new();
+    for round in 1.. {
+        let started = Instant::now();
+        if build(release)? {
+            let answers = Answers::load(&dir)?;
+            let current = run_inputs(day, &dir, inputs, &answers, release)?;
+            println!("Run {} finished in {:.1?}", round, started.elapsed());
+            print!("{}", report(&previous, &current, &answers));
+            previous = current;
+        } else {
+            println!("Run {} failed to build; answers of run {} are kept", round, round - 1);
+        }
+        println!("Watching {} for changes", dir.display());
+        wait_for_change(&events).map_err(|e| Error::io("Could not read file events").with_source(e))?;
+    }
+    Ok(())
+}
+
+/// Blocks until a watched file changes, then swallows the rest of the burst.
+fn wait_for_change(events: &Receiver<notify::Result<Event>>) -> notify::Result<()> {
+    loop {
+        let event = events.recv().map_err(|_| notify::Error::generic("The watcher stopped"))??;
+        let changed: Vec<&PathBuf> = event.paths.iter().filter(|path| is_watched(path)).collect();
+        if is_change(&event.kind) && !changed.is_empty() {
+            info!(?changed, "Files changed");
+            break;
+        }
+    }
+    thread::sleep(DEBOUNCE);
+    events.try_iter().try_for_each(|event| event.map(drop))
+}
+
+/// Writes, creations, removals and renames; reading a file, e.g. during the build, is not a change.
+fn is_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(modify) => !matches!(modify, ModifyKind::Metadata(_)),
+        EventKind::Access(access) => *access == AccessKind::Close(AccessMode::Write),
+        EventKind::Any | EventKind::Other => false,
+    }
+}
+
+fn is_watched(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|extension| WATCHED_EXTENSIONS.contains(&extension))
+}
+
+/// Builds the runner. Compiler errors go straight to the terminal; returns whether the build succeeded.
+fn build(release: bool) -> Result<bool, Error> {
+    let mut cargo = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo.args(["build", "--quiet", "--package", "advoc"]).current_dir(workspace_root());
+    if release {
+        cargo.arg("--release");
+    }
+    let status = cargo.status().map_err(|e| Error::io("Could not run cargo").with_source(e))?;
+    Ok(status.success())
+}
+
+fn binary(release: bool) -> PathBuf {
+    let target = env::var_os("CARGO_TARGET_DIR").map_or_else(|| workspace_root().join("target"), PathBuf::from);
+    let profile = if release { "release" } else { "debug" };
+    target.join(profile).join(format!("advoc{}", env::consts::EXE_SUFFIX))
+}
+
+fn run_inputs(day: &Day, dir: &Path, inputs: &[String], answers: &Answers, release: bool) -> Result<Results, Error> {
+    let inputs: BTreeSet<String> = if inputs.is_empty() {
+        input_names(&InputLocator::new(dir), answers)?
+    } else {
+        inputs.iter().cloned().collect()
+    };
+    let binary = binary(release);
+    let number = day.number.to_string();
+    let mut results = Results::new();
+    for input in inputs {
+        debug!(input, "Running");
+        let output = Command::new(&binary)
+            .args(["--log", "warn", "run", &number])
+            .args(["--input", &input, "--format", "json"])
+            .output()
+            .map_err(|e| Error::from(e).with_file(binary.display()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = first_error(&stderr);
+            results.extend(Part::ALL.map(|part| ((input.clone(), part), Err(message.clone()))));
+            continue;
+        }
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let record: Value = serde_json::from_str(line).map_err(|e| Error::parse("Unexpected output of advoc run").with_source(e))?;
+            let part = record["part"].as_u64().and_then(|part| u8::try_from(part).ok()).unwrap_or_default();
+            let answer = record["answer"].as_str().unwrap_or_default().to_string();
+            results.insert((input.clone(), Part::try_from(part)?), Ok(answer));
+        }
+    }
+    Ok(results)
+}
+
+/// The headline of a rendered error, falling back to the last line the child printed.
+fn first_error(stderr: &str) -> String {
+    stderr
+        .lines()
+        .find(|line| line.starts_with("error"))
+        .or_else(|| stderr.lines().rfind(|line| !line.trim().is_empty()))
+        .unwrap_or("advoc run failed")
+        .to_string()
+}
+
+/// One row per input and part, comparing each answer with the previous run and with `answers.toml`.
+/// Errors are listed below the table.
+pub fn report(previous: &Results, current: &Results, answers: &Answers) -> String {
+    let rows: Vec<[String; 5]> = current
+        .iter()
+        .map(|((input, part), outcome)| {
+            let answer = outcome.as_deref().unwrap_or("error");
+            let change = match previous.get(&(input.clone(), *part)) {
+                None => "new".to_string(),
+                Some(before) if before == outcome => "same".to_string(),
+                Some(Ok(before)) => format!("was {}", before),
+                Some(Err(_)) => "was error".to_string(),
+            };
+            let expected = match (answers.expected(input, *part), outcome) {
+                (None, _) => "-".to_string(),
+                (Some(expected), Ok(actual)) if expected == actual => "ok".to_string(),
+                (Some(expected), _) => format!("WRONG, expected {}", expected),
+            };
+            [input.clone(), part.number().to_string(), answer.to_string(), change, expected]
+        })
+        .collect();
+
+    let header = ["input", "part", "answer", "previous", "expected"].map(str::to_string);
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| rows.iter().chain([&header]).map(|row| row[column].len()).max().unwrap_or_default())
+        .collect();
+    let mut out = String::new();
+    for row in [&header].into_iter().chain(&rows) {
+        let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell)).collect();
+        let _ = writeln!(out, "{}", cells.join(" ").trim_end());
+    }
+    for ((input, part), outcome) in current {
+        if let Err(message) = outcome {
+            let _ = writeln!(out, "{} part {}: {}", input, part.number(), message);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::MetadataKind;
+
+    use super::*;
+
+    fn results(outcomes: &[(&str, Part, Outcome)]) -> Results {
+        outcomes
+            .iter()
+            .map(|(input, part, outcome)| ((input.to_string(), *part), outcome.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_report() {
+        let answers = Answers::parse("[example]\npart1 = 288\npart2 = 71503\n").unwrap();
+        let previous = results(&[
+            ("example", Part::One, Ok("288".to_string())),
+            ("example", Part::Two, Ok("7".to_string())),
+        ]);
+        let current = results(&[
+            ("example", Part::One, Ok("288".to_string())),
+            ("example", Part::Two, Ok("8".to_string())),
+            ("testdata", Part::One, Err("error[parse]: bad".to_string())),
+        ]);
+        assert_eq!(
+            report(&previous, &current, &answers),
+            "input    part answer previous expected\n\
+             example  1    288    same     ok\n\
+             example  2    8      was 7    WRONG, expected 71503\n\
+             testdata 1    error  new      -\n\
+             testdata part 1: error[parse]: bad\n"
+        );
+    }
+
+    #[test]
+    fn test_is_watched() {
+        assert!(is_watched(Path::new("day06/src/lib.rs")));
+        assert!(is_watched(Path::new("example.dat")));
+        assert!(!is_watched(Path::new("day06/src/.lib.rs.swp")));
+        assert!(!is_watched(Path::new("4913")));
+    }
+
+    #[test]
+    fn test_is_change() {
+        assert!(is_change(&EventKind::Access(AccessKind::Close(AccessMode::Write))));
+        assert!(is_change(&EventKind::Modify(ModifyKind::Any)));
+        assert!(!is_change(&EventKind::Access(AccessKind::Close(AccessMode::Read))));
+        assert!(!is_change(&EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any))));
+    }
+}
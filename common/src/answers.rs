@@ -0,0 +1,209 @@
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+use crate::{read_test_data, solve, Error, Part, Solution};
+
+/// Name of the file next to a day's inputs that records the expected answers.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// The expected answers of a day, per input name and part.
+///
+/// The file has one table per input, keyed like the inputs are named on the command line:
+///
+/// ```toml
+/// [testdata]
+/// part1 = 1734
+/// part2 = 70387
+///
+/// [example]
+/// part1 = 8
+/// ```
+///
+/// Answers may be written as integers or as strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    expected: BTreeMap<String, BTreeMap<Part, String>>,
+}
+
+impl Answers {
+    /// Reads `answers.toml` from `dir`. A day without one simply has no expected answers.
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        let path = dir.join(ANSWERS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = read_test_data(&path).map_err(|e| e.with_file(path.display()))?;
+        Self::parse(&text).map_err(|e| e.with_file(path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| {
+            let error = Error::parse(e.message().to_string());
+            match e.span() {
+                Some(span) => locate(error, text, span.start),
+                None => error,
+            }
+            .with_source(e)
+        })?;
+
+        let mut expected = BTreeMap::new();
+        for (input, parts) in table {
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| Error::validation(format!("Expected a table of answers for input '{}'", input)))?;
+            let mut answers = BTreeMap::new();
+            for (key, value) in parts {
+                let part = match key.as_str() {
+                    "part1" => Part::One,
+                    "part2" => Part::Two,
+                    _ => {
+                        return Err(Error::validation(format!(
+                            "Unknown key '{}.{}', expected part1 or part2",
+                            input, key
+                        )))
+                    }
+                };
+                let answer = match value {
+                    toml::Value::String(s) => s.clone(),
+                    toml::Value::Integer(n) => n.to_string(),
+                    _ => {
+                        return Err(Error::validation(format!(
+                            "The answer '{}.{}' must be a string or an integer",
+                            input, key
+                        )))
+                    }
+                };
+                answers.insert(part, answer);
+            }
+            expected.insert(input, answers);
+        }
+        Ok(Self { expected })
+    }
+
+    pub fn expected(&self, input: &str, part: Part) -> Option<&str> {
+        self.expected.get(input)?.get(&part).map(String::as_str)
+    }
+
+    /// The names of the inputs that have an entry, in alphabetical order.
+    pub fn inputs(&self) -> impl Iterator<Item = &str> {
+        self.expected.keys().map(String::as_str)
+    }
+
+    /// Records an answer and returns the one it replaced.
+    pub fn insert(&mut self, input: &str, part: Part, answer: impl Into<String>) -> Option<String> {
+        self.expected.entry(input.to_string()).or_default().insert(part, answer.into())
+    }
+
+    /// The answers in the format read by [`Answers::parse`]. Numeric answers are written as integers.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        for (input, answers) in &self.expected {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let _ = writeln!(out, "[{}]", input);
+            for (part, answer) in answers {
+                let value = match answer.parse::<i64>() {
+                    Ok(n) => toml::Value::Integer(n),
+                    Err(_) => toml::Value::String(answer.clone()),
+                };
+                let _ = writeln!(out, "part{} = {}", part.number(), value);
+            }
+        }
+        out
+    }
+
+    /// Writes `answers.toml` to `dir`, replacing the file and any comments in it.
+    pub fn save(&self, dir: &Path) -> Result<(), Error> {
+        let path = dir.join(ANSWERS_FILE);
+        fs::write(&path, self.to_toml()).map_err(|e| Error::from(e).with_file(path.display()))
+    }
+}
+
+/// Solves `input` from `dir` with `S` and compares the parts that have an expected answer in the
+/// `answers.toml` of `dir`. Parts without one are not run, so a fresh day passes trivially.
+pub fn check_answers<S: Solution>(dir: &Path, input: &str) -> Result<(), Error> {
+    let answers = Answers::load(dir)?;
+    let parts: Vec<Part> = Part::ALL
+        .into_iter()
+        .filter(|part| answers.expected(input, *part).is_some())
+        .collect();
+    if parts.is_empty() {
+        return Ok(());
+    }
+    let path = dir.join(format!("{}.dat", input));
+    let data = read_test_data(&path).map_err(|e| e.with_file(path.display()))?;
+    let mismatches: Vec<String> = solve::<S>(&data, &parts)
+        .map_err(|e| e.with_file(path.display()))?
+        .into_iter()
+        .filter_map(|answer| {
+            let expected = answers.expected(input, answer.part)?;
+            (expected != answer.value).then(|| format!("part {} is {}, expected {}", answer.part, answer.value, expected))
+        })
+        .collect();
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::validation(format!("Wrong answers for {}: {}", input, mismatches.join(", "))))
+    }
+}
+
+/// Asserts that the crate this is expanded in gets the answers recorded for an input, e.g.
+/// `assert_answers!(Day06, "example")`. See [`check_answers`].
+#[macro_export]
+macro_rules! assert_answers {
+    ($solution:ty, $input:expr) => {
+        if let Err(e) = $crate::check_answers::<$solution>($crate::input_locator!().dir(), $input) {
+            panic!("{}", e.render());
+        }
+    };
+}
+
+/// Attaches the line and column of byte `offset` in `text` to `error`.
+fn locate(error: Error, text: &str, offset: usize) -> Error {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = text[line_start..].lines().next().unwrap_or_default();
+    let column = text[line_start..offset].chars().count();
+    error
+        .with_columns(column..column + 1)
+        .with_line(text[..line_start].matches('\n').count() + 1, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("[testdata]\npart1 = 1734\npart2 = \"70387\"\n\n[example]\npart1 = 8\n").unwrap();
+        assert_eq!(answers.expected("testdata", Part::One), Some("1734"));
+        assert_eq!(answers.expected("testdata", Part::Two), Some("70387"));
+        assert_eq!(answers.expected("example", Part::Two), None);
+        assert_eq!(answers.expected("other", Part::One), None);
+        assert_eq!(answers.inputs().collect::<Vec<_>>(), vec!["example", "testdata"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Answers::parse("[testdata]\npart3 = 1\n").is_err());
+        assert!(Answers::parse("[testdata]\npart1 = 1.5\n").is_err());
+        let error = Answers::parse("[testdata]\npart1 = = 1\n").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+    }
+
+    #[test]
+    fn test_to_toml() {
+        let mut answers = Answers::default();
+        assert_eq!(answers.insert("testdata", Part::Two, "70387"), None);
+        answers.insert("example", Part::One, "8");
+        answers.insert("testdata", Part::One, "abc");
+        assert_eq!(answers.insert("example", Part::One, "9"), Some("8".to_string()));
+        let text = answers.to_toml();
+        assert_eq!(text, "[example]\npart1 = 9\n\n[testdata]\npart1 = \"abc\"\npart2 = 70387\n");
+        assert_eq!(Answers::parse(&text).unwrap(), answers);
+    }
+
+    #[test]
+    fn test_load_missing() {
+        assert_eq!(Answers::load(Path::new("/no/such/dir")).unwrap(), Answers::default());
+    }
+}
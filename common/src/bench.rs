@@ -0,0 +1,135 @@
+use std::time::{Duration, Instant};
+
+use crate::{Answer, Error, Part, Phase, Solution};
+
+/// Summary of the timings of one phase over all runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    /// Nearest-rank 95th percentile.
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarises `samples`, which must not be empty.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        samples.sort_unstable();
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        Self {
+            min: samples[0],
+            median,
+            p95: samples[(n * 95).div_ceil(100) - 1],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseStats {
+    pub phase: Phase,
+    pub stats: Stats,
+}
+
+/// Result of [`bench`]: the answers of the last run and the timings per phase.
+#[derive(Debug, Clone)]
+pub struct Bench {
+    pub runs: usize,
+    pub answers: Vec<Answer>,
+    pub phases: Vec<PhaseStats>,
+}
+
+/// Parses `data` and runs the requested parts `runs` times, timing every phase separately.
+///
+/// Each run parses from scratch, so the parse phase is measured as often as the parts.
+pub fn bench<S: Solution>(data: &str, parts: &[Part], runs: usize) -> Result<Bench, Error> {
+    if runs == 0 {
+        return Err(Error::validation("Benchmarking needs at least one run"));
+    }
+    let mut parse_samples = Vec::with_capacity(runs);
+    let mut part_samples = vec![Vec::with_capacity(runs); parts.len()];
+    let mut answers = Vec::new();
+    for _ in 0..runs {
+        let start = Instant::now();
+        let input = S::parse(data)?;
+        parse_samples.push(start.elapsed());
+
+        answers.clear();
+        for (part, samples) in parts.iter().zip(&mut part_samples) {
+            let start = Instant::now();
+            let value = match part {
+                Part::One => S::part_one(&input)?.to_string(),
+                Part::Two => S::part_two(&input)?.to_string(),
+            };
+            let duration = start.elapsed();
+            samples.push(duration);
+            answers.push(Answer {
+                part: *part,
+                value,
+                duration,
+            });
+        }
+    }
+
+    let mut phases = vec![PhaseStats {
+        phase: Phase::Parse,
+        stats: Stats::from_samples(parse_samples),
+    }];
+    phases.extend(parts.iter().zip(part_samples).map(|(part, samples)| PhaseStats {
+        phase: Phase::Part(*part),
+        stats: Stats::from_samples(samples),
+    }));
+    Ok(Bench { runs, answers, phases })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Display;
+
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+
+        fn parse(data: &str) -> Result<Self::Input, Error> {
+            Ok(data.split_whitespace().map(str::parse).collect::<Result<_, _>>()?)
+        }
+
+        fn part_one(input: &Self::Input) -> Result<impl Display, Error> {
+            Ok(input.iter().sum::<u32>())
+        }
+
+        fn part_two(input: &Self::Input) -> Result<impl Display, Error> {
+            Ok(input.iter().product::<u32>())
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(Stats::from_samples(vec![Duration::from_millis(3)]).p95, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_bench() {
+        let results = bench::<Sum>("2 3 4", &[Part::Two], 5).unwrap();
+        assert_eq!(results.runs, 5);
+        assert_eq!(results.answers.len(), 1);
+        assert_eq!((results.answers[0].part, results.answers[0].value.as_str()), (Part::Two, "24"));
+        let phases: Vec<Phase> = results.phases.iter().map(|phase| phase.phase).collect();
+        assert_eq!(phases, vec![Phase::Parse, Phase::Part(Part::Two)]);
+        assert!(bench::<Sum>("2 x", &Part::ALL, 5).is_err());
+        assert!(bench::<Sum>("2", &Part::ALL, 0).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+//! Checkpoints for computations that run long enough to be interrupted.
+//!
+//! A [`Checkpoint`] holds the state of a long loop, e.g. how far every range has been searched
+//! and the best result so far. Workers update it as they go; it is written to disk at most once
+//! per interval, and once more when the computation stops early. Opening the checkpoint of the
+//! same computation again resumes from that state, and finishing it removes the file.
+//!
+//! While a checkpoint is open, Ctrl-C sets [`interrupted`] instead of killing the process, so
+//! workers can stop and the state can be saved. A second Ctrl-C quits right away.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex, Once,
+    },
+    time::{Duration, Instant},
+};
+
+use tracing::{info, warn};
+
+use crate::Error;
+
+/// Environment variable overriding the directory checkpoints are written to.
+pub const DIR_ENV: &str = "ADVOC_CHECKPOINT_DIR";
+
+/// How often [`Checkpoint::update`] writes the state by default.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+const EXTENSION: &str = "checkpoint";
+
+/// Exit code of a process killed by SIGINT.
+const INTERRUPT_EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static OPEN_CHECKPOINTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether Ctrl-C was pressed while a checkpoint was open. Long loops poll this to stop early.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Installs the Ctrl-C handler once per process. Without an open checkpoint there is nothing to
+/// save, so the process exits as it would without the handler.
+fn handle_interrupts() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let installed = ctrlc::set_handler(|| {
+            if OPEN_CHECKPOINTS.load(Ordering::SeqCst) == 0 || INTERRUPTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(INTERRUPT_EXIT_CODE);
+            }
+            warn!("Interrupted, saving progress; press Ctrl-C again to quit right away");
+        });
+        if let Err(e) = installed {
+            warn!(error = %e, "Could not handle Ctrl-C, progress is only saved periodically");
+        }
+    });
+}
+
+/// The directory checkpoints are written to: `$ADVOC_CHECKPOINT_DIR`, or `target/checkpoints`
+/// in the workspace of the crate at `manifest_dir`.
+pub fn dir(manifest_dir: impl AsRef<Path>) -> PathBuf {
+    match env::var_os(DIR_ENV) {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let manifest_dir = manifest_dir.as_ref();
+            manifest_dir.parent().unwrap_or(manifest_dir).join("target").join("checkpoints")
+        }
+    }
+}
+
+/// The checkpoint directory of the crate this is expanded in, see [`dir`].
+#[macro_export]
+macro_rules! checkpoint_dir {
+    () => {
+        $crate::checkpoint::dir(env!("CARGO_MANIFEST_DIR"))
+    };
+}
+
+/// The state of a computation as it is written to a checkpoint file.
+pub trait State: Sized {
+    fn encode(&self) -> String;
+    fn decode(text: &str) -> Result<Self, Error>;
+}
+
+/// The state of one computation, shared by its workers and saved to disk from time to time.
+#[derive(Debug)]
+pub struct Checkpoint<S> {
+    path: PathBuf,
+    header: String,
+    interval: Duration,
+    resumed: bool,
+    inner: Mutex<Inner<S>>,
+    _open: Open,
+}
+
+#[derive(Debug)]
+struct Inner<S> {
+    state: S,
+    saved: Instant,
+}
+
+impl<S: State> Checkpoint<S> {
+    /// Opens the checkpoint of the computation `name` in `dir`. `key` identifies its input, so
+    /// that a checkpoint of another input is never resumed. Without a checkpoint to resume from,
+    /// the computation starts from `initial`.
+    pub fn open(dir: impl AsRef<Path>, name: &str, key: &str, initial: impl FnOnce() -> S) -> Result<Self, Error> {
+        let fingerprint = fingerprint(key);
+        let path = dir.as_ref().join(format!("{}-{}.{}", name, fingerprint, EXTENSION));
+        let header = format!("# {} {}", name, fingerprint);
+        let saved = match fs::read_to_string(&path) {
+            Ok(text) => Some(Self::decode(&header, &text).map_err(|e| e.with_file(path.display()))?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(Error::from(e).with_file(path.display())),
+        };
+        let resumed = saved.is_some();
+        if resumed {
+            info!(path = %path.display(), "Resuming from checkpoint");
+        }
+
+        handle_interrupts();
+        Ok(Self {
+            path,
+            header,
+            interval: DEFAULT_INTERVAL,
+            resumed,
+            inner: Mutex::new(Inner {
+                state: saved.unwrap_or_else(initial),
+                saved: Instant::now(),
+            }),
+            _open: Open::new(),
+        })
+    }
+
+    fn decode(header: &str, text: &str) -> Result<S, Error> {
+        match text.split_once('\n') {
+            Some((first, body)) if first == header => S::decode(body),
+            _ => Err(Error::parse(format!("Expected the checkpoint header '{}'", header))),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Where the state is saved.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the state was read from an earlier run.
+    pub fn resumed(&self) -> bool {
+        self.resumed
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner<S>> {
+        // A worker that panicked while updating cannot leave the state half written; the
+        // panic itself is reported when the worker is joined.
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Reads the current state.
+    pub fn read<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        f(&self.lock().state)
+    }
+
+    /// Changes the state, and saves it if the last save is longer ago than the interval.
+    pub fn update(&self, f: impl FnOnce(&mut S)) -> Result<(), Error> {
+        let mut inner = self.lock();
+        f(&mut inner.state);
+        if inner.saved.elapsed() >= self.interval {
+            self.write(&mut inner)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the state now, e.g. after the workers stopped because of an interrupt.
+    pub fn save(&self) -> Result<(), Error> {
+        self.write(&mut self.lock())
+    }
+
+    /// Writes next to the checkpoint first, so that being killed while writing cannot leave a
+    /// truncated checkpoint behind.
+    fn write(&self, inner: &mut Inner<S>) -> Result<(), Error> {
+        let temporary = self.path.with_extension(format!("{}.{}", EXTENSION, std::process::id()));
+        let write = || -> Result<(), Error> {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&temporary, format!("{}\n{}", self.header, inner.state.encode()))?;
+            fs::rename(&temporary, &self.path)?;
+            Ok(())
+        };
+        write().map_err(|e| e.with_file(self.path.display()))?;
+        inner.saved = Instant::now();
+        Ok(())
+    }
+
+    /// Ends the computation: the checkpoint is removed and the final state returned.
+    pub fn finish(self) -> Result<S, Error> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(Error::from(e).with_file(self.path.display())),
+            _ => Ok(self.inner.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner()).state),
+        }
+    }
+}
+
+/// Counts the open checkpoints for the Ctrl-C handler while it lives.
+#[derive(Debug)]
+struct Open;
+
+impl Open {
+    fn new() -> Self {
+        OPEN_CHECKPOINTS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for Open {
+    fn drop(&mut self) {
+        OPEN_CHECKPOINTS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// FNV-1a of `key`, which unlike the std hashers is the same in every build.
+fn fingerprint(key: &str) -> String {
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Counter(u64);
+
+    impl State for Counter {
+        fn encode(&self) -> String {
+            format!("{}\n", self.0)
+        }
+
+        fn decode(text: &str) -> Result<Self, Error> {
+            text.trim().parse().map(Counter).map_err(|_| Error::parse("Expected a count"))
+        }
+    }
+
+    #[test]
+    fn test_resume() {
+        let dir = env::temp_dir().join(format!("advoc-checkpoint-{}", std::process::id()));
+        let checkpoint = Checkpoint::open(&dir, "count", "input", || Counter(0)).unwrap();
+        assert!(!checkpoint.resumed());
+        checkpoint.update(|counter| counter.0 = 7).unwrap();
+        assert!(!checkpoint.path().exists());
+        checkpoint.save().unwrap();
+        assert_eq!(
+            fs::read_to_string(checkpoint.path()).unwrap(),
+            format!("# count {}\n7\n", fingerprint("input"))
+        );
+        drop(checkpoint);
+
+        let other = Checkpoint::open(&dir, "count", "other input", || Counter(0)).unwrap();
+        assert_eq!(other.read(|counter| counter.0), 0);
+
+        let checkpoint = Checkpoint::open(&dir, "count", "input", || Counter(0))
+            .unwrap()
+            .with_interval(Duration::ZERO);
+        assert!(checkpoint.resumed());
+        checkpoint.update(|counter| counter.0 += 1).unwrap();
+        assert_eq!(fs::read_to_string(checkpoint.path()).unwrap().lines().last(), Some("8"));
+        let path = checkpoint.path().to_path_buf();
+        assert_eq!(checkpoint.finish().unwrap(), Counter(8));
+        assert!(!path.exists());
+
+        fs::write(&path, "# count 0\n7\n").unwrap();
+        let error = Checkpoint::open(&dir, "count", "input", || Counter(0)).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(error.render().contains("Expected the checkpoint header"));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(""), "cbf29ce484222325");
+        assert_eq!(fingerprint("a"), "af63dc4c8601ec8c");
+    }
+}
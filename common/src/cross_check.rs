@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+
+use proptest::{
+    prop_assert_eq,
+    strategy::Strategy,
+    test_runner::{TestCaseResult, TestRunner},
+};
+
+/// Checks an optimized implementation against a slow but obviously right reference on inputs
+/// drawn from `strategy`, e.g. [`crate::samples`].
+///
+/// Panics with the first input the two disagree on, shrunk by proptest to a minimal one. The
+/// number of cases follows `PROPTEST_CASES`.
+#[track_caller]
+pub fn cross_check<S, R>(reference: impl Fn(&S::Value) -> R, optimized: impl Fn(&S::Value) -> R, strategy: S)
+where
+    S: Strategy,
+    R: PartialEq + Debug,
+{
+    let check = |value: S::Value| -> TestCaseResult {
+        prop_assert_eq!(optimized(&value), reference(&value));
+        Ok(())
+    };
+    if let Err(error) = TestRunner::default().run(&strategy, check) {
+        panic!("The optimized implementation disagrees with the reference: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agreeing_implementations() {
+        cross_check(|n: &u32| n * 2, |n: &u32| n << 1, 0..1000u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "minimal failing input: 101")]
+    fn test_disagreement_is_shrunk() {
+        cross_check(|n: &u32| n.min(&100) * 2, |n: &u32| n * 2, 0..1000u32);
+    }
+}
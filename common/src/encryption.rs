@@ -0,0 +1,201 @@
+//! Puzzle inputs stored encrypted, so that a public repository does not redistribute them.
+//!
+//! An input `testdata.dat` may be committed as `testdata.dat.enc` instead; [`read_test_data`]
+//! falls back to the encrypted file when the plain one is missing. The key is 32 bytes written
+//! as hex and is looked up in this order:
+//!
+//! 1. the `ADVOC_KEY` environment variable,
+//! 2. the file named by `ADVOC_KEY_FILE`,
+//! 3. a `.advoc-key` file in the input's directory or any directory above it.
+//!
+//! Files are XChaCha20-Poly1305 encrypted: a magic header, a random nonce and the ciphertext, so a
+//! wrong key or a corrupted file is detected rather than producing garbage input.
+//!
+//! [`read_test_data`]: crate::read_test_data
+
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use rand::RngCore;
+
+use crate::Error;
+
+/// Environment variable holding the key as hex.
+pub const KEY_ENV: &str = "ADVOC_KEY";
+
+/// Environment variable naming a file that holds the key as hex.
+pub const KEY_FILE_ENV: &str = "ADVOC_KEY_FILE";
+
+/// Key file searched for from an input's directory upwards.
+pub const KEY_FILE: &str = ".advoc-key";
+
+/// Extension appended to the name of an encrypted input.
+pub const EXTENSION: &str = "enc";
+
+const MAGIC: &[u8] = b"ADVOCENC1";
+const NONCE_LEN: usize = 24;
+
+/// A symmetric key for encrypting inputs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Key([u8; 32]);
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+impl Key {
+    /// A new random key.
+    pub fn generate() -> Self {
+        let mut key = [0; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Self(key)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, Error> {
+        let hex = hex.trim();
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(Error::parse("A key must be 64 hex digits"));
+        }
+        let mut key = [0; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| Error::parse("A key must be 64 hex digits"))?;
+        }
+        Ok(Self(key))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Reads a key file.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|hex| Self::from_hex(&hex))
+            .map_err(|e| e.with_file(path.display()))
+    }
+
+    /// The key from the environment, or from the nearest key file at or above `dir`.
+    pub fn find(dir: &Path) -> Result<Self, Error> {
+        Self::lookup(dir)?.ok_or_else(|| {
+            Error::validation(format!(
+                "No key found: set {} or {}, or create {} next to the inputs",
+                KEY_ENV, KEY_FILE_ENV, KEY_FILE
+            ))
+        })
+    }
+
+    /// Like [`Key::find`], but a missing key is not an error. An invalid one still is.
+    pub fn lookup(dir: &Path) -> Result<Option<Self>, Error> {
+        if let Ok(hex) = env::var(KEY_ENV) {
+            return Self::from_hex(&hex)
+                .map(Some)
+                .map_err(|e| Error::parse(format!("Invalid {}: {}", KEY_ENV, e.message())));
+        }
+        if let Some(path) = env::var_os(KEY_FILE_ENV) {
+            return Self::load(Path::new(&path)).map(Some);
+        }
+        match dir.ancestors().map(|dir| dir.join(KEY_FILE)).find(|path| path.is_file()) {
+            Some(path) => Self::load(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The path of the encrypted copy of `path`, e.g. `testdata.dat.enc`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(EXTENSION);
+    path.with_file_name(name)
+}
+
+pub fn encrypt(key: &Key, plaintext: &[u8]) -> Vec<u8> {
+    let mut nonce = [0; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = XChaCha20Poly1305::new(&key.0.into())
+        .encrypt(XNonce::from_slice(&nonce), plaintext)
+        .expect("encrypting a buffer in memory cannot fail");
+    [MAGIC, &nonce, &ciphertext].concat()
+}
+
+pub fn decrypt(key: &Key, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let body = data
+        .strip_prefix(MAGIC)
+        .filter(|body| body.len() >= NONCE_LEN)
+        .ok_or_else(|| Error::parse("Not an encrypted input"))?;
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(&key.0.into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Error::validation("Could not decrypt: wrong key or corrupted file"))
+}
+
+/// Decrypts the file at `path` with the key found for its directory.
+pub fn read_encrypted(path: &Path) -> Result<String, Error> {
+    let read = || -> Result<String, Error> {
+        let key = Key::find(path.parent().unwrap_or(Path::new(".")))?;
+        let plaintext = decrypt(&key, &fs::read(path)?)?;
+        String::from_utf8(plaintext).map_err(|_| Error::parse("The decrypted input is not UTF-8"))
+    };
+    read().map_err(|e| e.with_file(path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = Key::generate();
+        let encrypted = encrypt(&key, b"Time: 7 15 30\n");
+        assert!(encrypted.starts_with(MAGIC));
+        assert_ne!(encrypt(&key, b"Time: 7 15 30\n"), encrypted);
+        assert_eq!(decrypt(&key, &encrypted).unwrap(), b"Time: 7 15 30\n");
+
+        assert!(decrypt(&Key::generate(), &encrypted).is_err());
+        let mut corrupted = encrypted.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&key, &corrupted).is_err());
+        assert!(decrypt(&key, b"Time: 7 15 30\n").is_err());
+    }
+
+    #[test]
+    fn test_key_hex() {
+        let key = Key::generate();
+        assert_eq!(Key::from_hex(&format!("{}\n", key.to_hex())).unwrap(), key);
+        assert!(Key::from_hex("abc").is_err());
+        assert!(Key::from_hex(&"g".repeat(64)).is_err());
+        assert_eq!(format!("{:?}", key), "Key(..)");
+    }
+
+    #[test]
+    fn test_read_test_data_falls_back_to_encrypted() {
+        let dir = env::temp_dir().join(format!("advoc-encryption-{}", std::process::id()));
+        let input = dir.join("day06/testdata.dat");
+        fs::create_dir_all(input.parent().unwrap()).unwrap();
+        let key = Key::generate();
+        fs::write(dir.join(KEY_FILE), key.to_hex()).unwrap();
+        fs::write(encrypted_path(&input), encrypt(&key, b"Time: 7\n")).unwrap();
+        let read = crate::read_test_data(&input);
+        fs::remove_dir_all(&dir).unwrap();
+        if env::var_os(KEY_ENV).is_none() && env::var_os(KEY_FILE_ENV).is_none() {
+            assert_eq!(read.unwrap(), "Time: 7\n");
+        }
+    }
+
+    #[test]
+    fn test_encrypted_path() {
+        assert_eq!(
+            encrypted_path(Path::new("/aoc/day06/testdata.dat")),
+            PathBuf::from("/aoc/day06/testdata.dat.enc")
+        );
+    }
+}
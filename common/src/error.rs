@@ -1,31 +1,290 @@
-use std::{fmt::Display, io, num::ParseIntError};
+use std::{
+    fmt::{Display, Write},
+    io,
+    num::{IntErrorKind, ParseIntError},
+    ops::Range,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    Io,
+    Parse,
+    Validation,
+    Overflow,
+    Other,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ErrorKind::Io => "io",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Validation => "validation",
+            ErrorKind::Overflow => "overflow",
+            ErrorKind::Other => "other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A diagnostic that knows which input, line and columns it is about.
+///
+/// Lexers attach the columns, the code walking the lines attaches the line and the code that
+/// opened the input attaches the file name. [`Error::render`] prints the offending line with the
+/// columns underlined.
+#[derive(Debug)]
+pub struct Error {
+    inner: Box<Diagnostic>,
+}
 
 #[derive(Debug)]
-pub enum Error {
-    GenericError(String),
-    SyntaxError(String),
+struct Diagnostic {
+    kind: ErrorKind,
+    message: String,
+    file: Option<String>,
+    line: Option<SourceLine>,
+    columns: Option<Range<usize>>,
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+}
+
+#[derive(Debug, Clone)]
+struct SourceLine {
+    number: usize,
+    text: String,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            inner: Box::new(Diagnostic {
+                kind,
+                message: message.into(),
+                file: None,
+                line: None,
+                columns: None,
+                source: None,
+            }),
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Io, message)
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Parse, message)
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Validation, message)
+    }
+
+    pub fn overflow(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Overflow, message)
+    }
+
+    pub fn other(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Other, message)
+    }
+
+    /// Sets the input file, unless an inner layer already did.
+    pub fn with_file(mut self, file: impl Display) -> Self {
+        self.inner.file.get_or_insert_with(|| file.to_string());
+        self
+    }
+
+    /// Sets the 1-based line number and the text of that line, unless an inner layer already did.
+    pub fn with_line(mut self, number: usize, text: &str) -> Self {
+        self.inner.line.get_or_insert_with(|| SourceLine {
+            number,
+            text: text.to_string(),
+        });
+        self
+    }
+
+    /// Sets the 0-based character columns the error is about, unless an inner layer already did.
+    pub fn with_columns(mut self, columns: Range<usize>) -> Self {
+        self.inner.columns.get_or_insert(columns);
+        self
+    }
+
+    pub fn with_source(mut self, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+        self.inner.source = Some(Box::new(source));
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.inner.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.inner.message
+    }
+
+    pub fn file(&self) -> Option<&str> {
+        self.inner.file.as_deref()
+    }
+
+    /// The 1-based line number.
+    pub fn line(&self) -> Option<usize> {
+        self.inner.line.as_ref().map(|line| line.number)
+    }
+
+    /// The 1-based column of the first underlined character.
+    pub fn column(&self) -> Option<usize> {
+        self.inner.columns.as_ref().map(|columns| columns.start + 1)
+    }
+
+    /// Renders the error with its location and, if known, the offending line underlined:
+    ///
+    /// ```text
+    /// error[parse]: Unexpected character: '#'
+    ///  --> day06/testdata.dat:2:17
+    ///   |
+    /// 2 | Distance:   356 # 1378
+    ///   |                 ^
+    /// ```
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "error[{}]: {}", self.inner.kind, self.inner.message);
+
+        if let Some(location) = self.location() {
+            let gutter = self.inner.line.as_ref().map(|line| line.number.to_string().len()).unwrap_or(1);
+            let _ = writeln!(out, "{:gutter$}--> {}", "", location, gutter = gutter);
+            if let Some(line) = &self.inner.line {
+                let _ = writeln!(out, "{:gutter$} |", "", gutter = gutter);
+                let _ = writeln!(out, "{} | {}", line.number, line.text);
+                if let Some(columns) = &self.inner.columns {
+                    let width = columns.len().max(1);
+                    let _ = writeln!(
+                        out,
+                        "{:gutter$} | {:indent$}{}",
+                        "",
+                        "",
+                        "^".repeat(width),
+                        gutter = gutter,
+                        indent = columns.start
+                    );
+                }
+            }
+        }
+
+        let mut source = std::error::Error::source(self);
+        let mut previous = self.inner.message.clone();
+        while let Some(cause) = source {
+            let cause_message = cause.to_string();
+            if cause_message != previous {
+                let _ = writeln!(out, "caused by: {}", cause_message);
+            }
+            previous = cause_message;
+            source = cause.source();
+        }
+        out
+    }
+
+    fn location(&self) -> Option<String> {
+        let mut location = match (&self.inner.file, &self.inner.line) {
+            (None, None) => return None,
+            (Some(file), None) => return Some(file.clone()),
+            (None, Some(line)) => format!("line {}", line.number),
+            (Some(file), Some(line)) => format!("{}:{}", file, line.number),
+        };
+        if let Some(column) = self.column() {
+            location += &format!(":{}", column);
+        }
+        Some(location)
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Error: {}", self)
+        write!(f, "{} error: {}", self.inner.kind, self.inner.message)?;
+        if let Some(location) = self.location() {
+            write!(f, " (at {})", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner
+            .source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
     }
 }
 
 impl From<io::Error> for Error {
     fn from(v: io::Error) -> Self {
-        Error::GenericError(v.to_string())
+        Error::io(v.to_string()).with_source(v)
     }
 }
 
 impl From<ParseIntError> for Error {
     fn from(v: ParseIntError) -> Self {
-        Error::GenericError(v.to_string())
+        let kind = match v.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => ErrorKind::Overflow,
+            _ => ErrorKind::Parse,
+        };
+        Error::new(kind, v.to_string()).with_source(v)
     }
 }
 
 impl From<strum::ParseError> for Error {
     fn from(v: strum::ParseError) -> Self {
-        Error::GenericError(v.to_string())
+        Error::parse(v.to_string()).with_source(v)
+    }
+}
+
+/// Parses `data` line by line, attaching the line number and text to any error.
+pub fn parse_lines<T>(data: &str, mut parse: impl FnMut(&str) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|e| e.with_line(i + 1, line)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let error = Error::parse("Unexpected character: '#'")
+            .with_columns(16..17)
+            .with_line(2, "Distance:   356 # 1378")
+            .with_file("day06/testdata.dat");
+        assert_eq!(
+            error.render(),
+            r"error[parse]: Unexpected character: '#'
+ --> day06/testdata.dat:2:17
+  |
+2 | Distance:   356 # 1378
+  |                 ^
+"
+        );
+        assert_eq!(
+            error.to_string(),
+            "parse error: Unexpected character: '#' (at day06/testdata.dat:2:17)"
+        );
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let error = Error::parse("Game ID could not be parsed").with_source("99999999999999999999".parse::<u16>().unwrap_err());
+        assert!(std::error::Error::source(&error).is_some());
+        assert!(error.render().contains("caused by: number too large"));
+        assert_eq!(
+            Error::from("99999999999999999999".parse::<i64>().unwrap_err()).kind(),
+            ErrorKind::Overflow
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let error = parse_lines("1\n2\nx\n4", |line| Ok(line.parse::<i32>()?)).unwrap_err();
+        assert_eq!(error.line(), Some(3));
+        assert_eq!(error.kind(), ErrorKind::Parse);
     }
 }
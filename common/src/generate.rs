@@ -0,0 +1,165 @@
+use std::fmt::Debug;
+
+use rand::{rngs::StdRng, SeedableRng};
+use tracing::debug;
+
+use crate::{Error, Solution};
+
+/// Produces random but valid puzzle input in a day's format.
+///
+/// Generators back the property tests of the day crates and `advoc generate`, so inputs can be
+/// larger or stranger than the examples.
+pub trait Generate {
+    /// The most records an input can hold, e.g. because records are numbered with a small type.
+    const MAX_SIZE: usize = usize::MAX;
+
+    /// An input with `size` records: games, cards, hands, races, map entries, or rows of a grid.
+    /// `size` is at most [`Generate::MAX_SIZE`].
+    fn generate(rng: &mut StdRng, size: usize) -> String;
+}
+
+/// Generates an input of `G` from a seed, so the same seed always yields the same input.
+pub fn generate<G: Generate>(seed: u64, size: usize) -> Result<String, Error> {
+    if size > G::MAX_SIZE {
+        return Err(Error::validation(format!(
+            "An input holds at most {} records, not {}",
+            G::MAX_SIZE,
+            size
+        )));
+    }
+    Ok(G::generate(&mut StdRng::seed_from_u64(seed), size))
+}
+
+/// Produces a synthetic input with the shape and statistical profile of a real one, so that
+/// benchmarks can be shared and reproduced without sharing the personal input.
+///
+/// A profile keeps what drives the running time (counts, magnitudes, distributions), never the
+/// values themselves in their original order.
+pub trait Anonymize: Solution {
+    type Profile: Debug;
+
+    /// Measures a parsed input.
+    fn profile(input: &Self::Input) -> Self::Profile;
+
+    /// A new input drawn to match `profile`.
+    fn synthesize(rng: &mut StdRng, profile: &Self::Profile) -> String;
+}
+
+/// Parses `data` with `A` and synthesizes a look-alike from a seed.
+pub fn anonymize<A: Anonymize>(data: &str, seed: u64) -> Result<String, Error> {
+    let profile = A::profile(&A::parse(data)?);
+    debug!(?profile, "Profiled input");
+    Ok(A::synthesize(&mut StdRng::seed_from_u64(seed), &profile))
+}
+
+/// An input generated for a property test, with the seed and size it came from.
+#[cfg(feature = "proptest")]
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+}
+
+#[cfg(feature = "proptest")]
+impl Sample {
+    /// The look-alike of the input synthesized from the same seed.
+    pub fn anonymize<A: Anonymize>(&self) -> Result<String, Error> {
+        anonymize::<A>(&self.input, self.seed)
+    }
+}
+
+/// Inputs of `G` with a number of records in `sizes`, capped at [`Generate::MAX_SIZE`], drawn
+/// from random seeds. Failures shrink towards fewer records.
+#[cfg(feature = "proptest")]
+pub fn samples<G: Generate>(sizes: std::ops::Range<usize>) -> impl proptest::strategy::Strategy<Value = Sample> {
+    use proptest::prelude::*;
+
+    let sizes = sizes.start..sizes.end.min(G::MAX_SIZE.saturating_add(1));
+    (any::<u64>(), sizes).prop_map(|(seed, size)| Sample {
+        seed,
+        size,
+        input: G::generate(&mut StdRng::seed_from_u64(seed), size),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Display;
+
+    use rand::Rng;
+
+    use super::*;
+
+    struct Numbers;
+
+    impl Generate for Numbers {
+        fn generate(rng: &mut StdRng, size: usize) -> String {
+            (0..size).map(|_| format!("{}\n", rng.gen_range(0..1000))).collect()
+        }
+    }
+
+    impl Solution for Numbers {
+        type Input = Vec<u32>;
+
+        fn parse(data: &str) -> Result<Self::Input, Error> {
+            data.lines().map(|line| Ok(line.parse()?)).collect()
+        }
+
+        fn part_one(input: &Self::Input) -> Result<impl Display, Error> {
+            Ok(input.iter().sum::<u32>())
+        }
+
+        fn part_two(input: &Self::Input) -> Result<impl Display, Error> {
+            Ok(input.len())
+        }
+    }
+
+    /// Count and largest value.
+    impl Anonymize for Numbers {
+        type Profile = (usize, u32);
+
+        fn profile(input: &Self::Input) -> Self::Profile {
+            (input.len(), input.iter().copied().max().unwrap_or_default())
+        }
+
+        fn synthesize(rng: &mut StdRng, (count, max): &Self::Profile) -> String {
+            (0..*count).map(|_| format!("{}\n", rng.gen_range(0..=*max))).collect()
+        }
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let data = "5\n900\n42\n";
+        let synthetic = anonymize::<Numbers>(data, 3).unwrap();
+        assert_eq!(synthetic, anonymize::<Numbers>(data, 3).unwrap());
+        let numbers = Numbers::parse(&synthetic).unwrap();
+        assert_eq!(numbers.len(), 3);
+        assert!(numbers.iter().all(|n| *n <= 900));
+        assert!(anonymize::<Numbers>("x\n", 3).is_err());
+    }
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        assert_eq!(generate::<Numbers>(7, 20).unwrap(), generate::<Numbers>(7, 20).unwrap());
+        assert_ne!(generate::<Numbers>(7, 20).unwrap(), generate::<Numbers>(8, 20).unwrap());
+        assert_eq!(generate::<Numbers>(7, 20).unwrap().lines().count(), 20);
+    }
+
+    struct Pairs;
+
+    impl Generate for Pairs {
+        const MAX_SIZE: usize = 2;
+
+        fn generate(rng: &mut StdRng, size: usize) -> String {
+            Numbers::generate(rng, size)
+        }
+    }
+
+    #[test]
+    fn test_generate_rejects_oversized_inputs() {
+        assert_eq!(generate::<Pairs>(7, 2).unwrap().lines().count(), 2);
+        let error = generate::<Pairs>(7, 3).unwrap_err();
+        assert_eq!(error.message(), "An input holds at most 2 records, not 3");
+    }
+}
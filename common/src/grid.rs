@@ -0,0 +1,349 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::Error;
+
+/// A cell position. Rows grow downwards, columns to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+
+    /// The position `d_row` rows and `d_col` columns away, if it does not leave the first quadrant.
+    pub fn offset(&self, d_row: isize, d_col: isize) -> Option<Pos> {
+        Some(Pos {
+            row: self.row.checked_add_signed(d_row)?,
+            col: self.col.checked_add_signed(d_col)?,
+        })
+    }
+}
+
+impl Display for Pos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const NEIGHBOURS_8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from rows, which must all have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, Error> {
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::validation(format!(
+                    "Row {} has {} cells, expected {}",
+                    i + 1,
+                    row.len(),
+                    width
+                )));
+            }
+            cells.extend(row);
+        }
+        Ok(Self { width, height, cells })
+    }
+
+    /// Parses every character of `s` with `parse`; each line is a row.
+    pub fn parse_with(s: &str, mut parse: impl FnMut(char) -> Result<T, Error>) -> Result<Self, Error> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(col, c)| parse(c).map_err(|e| e.with_columns(col..col + 1)))
+                .collect::<Result<Vec<T>, Error>>()
+                .map_err(|e| e.with_line(i + 1, line))?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(Error::validation(format!("Row has {} cells, expected {}", row.len(), first.len()))
+                        .with_columns(row.len().min(first.len())..row.len().max(first.len()))
+                        .with_line(i + 1, line));
+                }
+            }
+            rows.push(row);
+        }
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.row < self.height && pos.col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            self.cells.get(pos.row * self.width + pos.col)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            self.cells.get_mut(pos.row * self.width + pos.col)
+        } else {
+            None
+        }
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.height).flat_map(move |row| (0..self.width).map(move |col| Pos::new(row, col)))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The up to 4 orthogonal neighbours of `pos` that lie inside the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &NEIGHBOURS_4)
+    }
+
+    /// The up to 8 orthogonal and diagonal neighbours of `pos` that lie inside the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &NEIGHBOURS_8)
+    }
+
+    fn neighbours(&self, pos: Pos, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = Pos> + '_ {
+        offsets
+            .iter()
+            .filter_map(move |(d_row, d_col)| pos.offset(*d_row, *d_col))
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row < self.height {
+            Some(&self.cells[row * self.width..(row + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, T> {
+        // `chunks` panics on a zero width, an empty grid simply has no rows
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .skip(col)
+            .step_by(width.max(1))
+            .take(if col < width { self.height } else { 0 })
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.column(col))
+    }
+
+    /// The positions of the rectangle spanned by the two corners (inclusive), clipped to the grid.
+    pub fn region(&self, top_left: Pos, bottom_right: Pos) -> impl Iterator<Item = Pos> {
+        let rows = top_left.row..=bottom_right.row.min(self.height.saturating_sub(1));
+        let cols = top_left.col..=bottom_right.col.min(self.width.saturating_sub(1));
+        let (width, height) = (self.width, self.height);
+        rows.filter(move |_| width > 0 && height > 0)
+            .flat_map(move |row| cols.clone().map(move |col| Pos::new(row, col)))
+    }
+
+    /// The positions reachable from `start` through orthogonal neighbours whose cells satisfy `connects`.
+    /// `start` itself is included if its cell satisfies `connects`.
+    pub fn flood_fill(&self, start: Pos, connects: impl Fn(&T) -> bool) -> HashSet<Pos> {
+        let mut filled = HashSet::new();
+        let mut queue = VecDeque::new();
+        if self.get(start).is_some_and(&connects) {
+            filled.insert(start);
+            queue.push_back(start);
+        }
+        while let Some(pos) = queue.pop_front() {
+            for neighbour in self.neighbours4(pos) {
+                if !filled.contains(&neighbour) && connects(&self[neighbour]) {
+                    filled.insert(neighbour);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        filled
+    }
+
+    /// Maps every cell, keeping the shape.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid of the given size with every cell set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    pub fn transpose(&self) -> Self {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flat_map(|column| column.cloned()).collect(),
+        }
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotate_clockwise(&self) -> Self {
+        let mut rotated = self.transpose();
+        for row in rotated.cells.chunks_mut(rotated.width.max(1)) {
+            row.reverse();
+        }
+        rotated
+    }
+
+    /// Rotates the grid by 90 degrees counter-clockwise.
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        let transposed = self.transpose();
+        Self {
+            width: transposed.width,
+            height: transposed.height,
+            cells: transposed.rows().rev().flat_map(|row| row.iter().cloned()).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{} is outside of the {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{} is outside of the {}x{} grid", pos, width, height))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, Ok)
+    }
+}
+
+/// Prints the grid one row per line, without separators between cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "abc\ndef\n";
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid<char> = GRID.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos::new(1, 2)], 'f');
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+        assert_eq!(grid.to_string(), GRID);
+
+        let error = "abc\nde\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!(error.line(), Some(2));
+
+        let digits = Grid::parse_with("12\n3x", |c| c.to_digit(10).ok_or_else(|| Error::parse("Not a digit"))).unwrap_err();
+        assert_eq!((digits.line(), digits.column()), (Some(2), Some(2)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid: Grid<char> = GRID.parse().unwrap();
+        assert_eq!(
+            grid.neighbours4(Pos::new(0, 0)).collect::<Vec<_>>(),
+            vec![Pos::new(0, 1), Pos::new(1, 0)]
+        );
+        assert_eq!(grid.neighbours8(Pos::new(0, 1)).count(), 5);
+        assert_eq!(grid.neighbours8(Pos::new(1, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_rows_columns_region() {
+        let grid: Grid<char> = GRID.parse().unwrap();
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.columns().count(), 3);
+        let region: Vec<Pos> = grid.region(Pos::new(0, 1), Pos::new(5, 5)).collect();
+        assert_eq!(region, vec![Pos::new(0, 1), Pos::new(0, 2), Pos::new(1, 1), Pos::new(1, 2)]);
+    }
+
+    #[test]
+    fn test_transpose_rotate() {
+        let grid: Grid<char> = GRID.parse().unwrap();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.rotate_clockwise().rotate_counter_clockwise(), grid);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid: Grid<char> = "..#.\n.##.\n#...".parse().unwrap();
+        let filled = grid.flood_fill(Pos::new(0, 0), |c| *c == '.');
+        assert_eq!(filled.len(), 3);
+        assert!(!filled.contains(&Pos::new(0, 3)));
+        assert_eq!(grid.flood_fill(Pos::new(0, 3), |c| *c == '.').len(), 5);
+        assert!(grid.flood_fill(Pos::new(0, 2), |c| *c == '.').is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    env,
+    fmt::Display,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{read_test_data, Error};
+
+/// Environment variable that selects the puzzle input when no command line flag is given.
+pub const INPUT_ENV: &str = "ADVOC_INPUT";
+
+/// Name of the input read when neither a flag nor `ADVOC_INPUT` select one.
+pub const DEFAULT_INPUT: &str = "testdata";
+
+/// Where puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    pub fn read(&self) -> Result<String, Error> {
+        match self {
+            Input::Stdin => {
+                let mut data = String::new();
+                io::stdin().read_to_string(&mut data)?;
+                Ok(data)
+            }
+            Input::File(path) => read_test_data(path).map_err(|e| e.with_file(path.display())),
+        }
+    }
+
+    /// Short name of the input as used in `answers.toml`: `-` for stdin, otherwise the file name
+    /// without its extension.
+    pub fn name(&self) -> String {
+        match self {
+            Input::Stdin => "-".to_string(),
+            Input::File(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+        }
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::Stdin => write!(f, "<stdin>"),
+            Input::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Resolves input specs against a day crate's directory.
+///
+/// A spec is either `-` for stdin, a bare name like `example` which maps to `<dir>/example.dat`,
+/// or a path, which is taken as is.
+#[derive(Debug, Clone)]
+pub struct InputLocator {
+    dir: PathBuf,
+}
+
+impl InputLocator {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn locate(&self, spec: &str) -> Input {
+        if spec == "-" {
+            Input::Stdin
+        } else if is_name(spec) {
+            Input::File(self.dir.join(format!("{}.dat", spec)))
+        } else {
+            Input::File(PathBuf::from(spec))
+        }
+    }
+
+    /// Picks the input from the command line flag, then `ADVOC_INPUT`, then `default`.
+    pub fn select(&self, flag: Option<&str>, default: &str) -> Input {
+        match flag {
+            Some(spec) => self.locate(spec),
+            None => match env::var(INPUT_ENV) {
+                Ok(spec) if !spec.is_empty() => self.locate(&spec),
+                _ => self.locate(default),
+            },
+        }
+    }
+
+    pub fn read(&self, spec: &str) -> Result<String, Error> {
+        self.locate(spec).read()
+    }
+}
+
+fn is_name(spec: &str) -> bool {
+    !spec.is_empty() && spec.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// An [`InputLocator`] for the directory of the crate this is expanded in.
+#[macro_export]
+macro_rules! input_locator {
+    () => {
+        $crate::InputLocator::new(env!("CARGO_MANIFEST_DIR"))
+    };
+}
+
+/// Reads an input of the crate this is expanded in, e.g. `read_input!("example")`.
+#[macro_export]
+macro_rules! read_input {
+    ($spec:expr) => {
+        $crate::input_locator!().read($spec)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate() {
+        let locator = InputLocator::new("/aoc/day06");
+        assert_eq!(locator.locate("-"), Input::Stdin);
+        assert_eq!(locator.locate("example2"), Input::File(PathBuf::from("/aoc/day06/example2.dat")));
+        assert_eq!(locator.locate("other.dat"), Input::File(PathBuf::from("other.dat")));
+        assert_eq!(locator.locate("example2").name(), "example2");
+        assert_eq!(locator.locate("-").name(), "-");
+        assert_eq!(
+            locator.locate("../day05/testdata.dat"),
+            Input::File(PathBuf::from("../day05/testdata.dat"))
+        );
+    }
+
+    #[test]
+    fn test_select() {
+        let locator = InputLocator::new("/aoc/day06");
+        assert_eq!(locator.select(Some("example"), DEFAULT_INPUT), locator.locate("example"));
+    }
+
+    #[test]
+    fn test_read_input() {
+        assert!(read_input!("no-such-input").is_err());
+        assert!(input_locator!().dir().join("Cargo.toml").exists());
+    }
+}
@@ -1,9 +1,38 @@
 use std::{fs, path::Path};
 
+mod answers;
+mod bench;
+pub mod checkpoint;
+#[cfg(feature = "proptest")]
+mod cross_check;
+pub mod encryption;
 mod error;
-pub use error::Error;
+mod generate;
+mod grid;
+mod input;
+pub mod memory;
+pub mod parser;
+pub mod progress;
+mod solution;
+pub mod telemetry;
+pub use answers::{check_answers, Answers, ANSWERS_FILE};
+pub use bench::{bench, Bench, PhaseStats, Stats};
+#[cfg(feature = "proptest")]
+pub use cross_check::cross_check;
+pub use error::{parse_lines, Error, ErrorKind};
+pub use generate::{anonymize, generate, Anonymize, Generate};
+#[cfg(feature = "proptest")]
+pub use generate::{samples, Sample};
+pub use grid::{Grid, Pos};
+pub use input::{Input, InputLocator, DEFAULT_INPUT, INPUT_ENV};
+pub use solution::{solve, Answer, Part, Phase, Solution};
 
+/// Reads an input, decrypting `<path>.enc` if only the encrypted copy is present.
 pub fn read_test_data(path: &Path) -> Result<String, Error> {
+    let encrypted = encryption::encrypted_path(path);
+    if !path.exists() && encrypted.exists() {
+        return encryption::read_encrypted(&encrypted);
+    }
     let data = fs::read_to_string(path)?;
     Ok(data)
 }
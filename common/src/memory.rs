@@ -0,0 +1,212 @@
+//! Allocation accounting per phase.
+//!
+//! A binary opts in by installing [`CountingAllocator`] as its global allocator. It forwards to
+//! the system allocator and only starts counting once [`enable`] is called, so an installed but
+//! unused counter costs one relaxed load per allocation.
+//!
+//! ```
+//! #[global_allocator]
+//! static ALLOCATOR: common::memory::CountingAllocator = common::memory::CountingAllocator;
+//! # fn main() {}
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+    time::Instant,
+};
+
+use crate::{Answer, Error, Part, Phase, Solution};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+/// Bytes in use, counted from when counting was enabled. Memory allocated before and freed
+/// after can make it negative, which is why only differences of it are reported.
+static CURRENT: AtomicI64 = AtomicI64::new(0);
+static PEAK: AtomicI64 = AtomicI64::new(0);
+
+/// A global allocator that counts allocations, allocated bytes and the bytes in use.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CountingAllocator;
+
+fn record(allocated: usize, freed: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    if allocated > 0 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(allocated as u64, Ordering::Relaxed);
+    }
+    let change = allocated as i64 - freed as i64;
+    let current = CURRENT.fetch_add(change, Ordering::Relaxed) + change;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Starts counting. Fails if [`CountingAllocator`] is not the global allocator, since nothing
+/// would be counted then.
+pub fn enable() -> Result<(), Error> {
+    ENABLED.store(true, Ordering::Relaxed);
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    drop(std::hint::black_box(Box::new(0_u64)));
+    if ALLOCATIONS.load(Ordering::Relaxed) == before {
+        ENABLED.store(false, Ordering::Relaxed);
+        return Err(Error::validation(
+            "Allocations cannot be counted: CountingAllocator is not the global allocator",
+        ));
+    }
+    Ok(())
+}
+
+/// What a piece of code allocated. Allocations of other threads running at the same time are
+/// included, which is what a part that spawns its own workers wants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// Number of allocations, reallocations included.
+    pub allocations: u64,
+    /// Bytes requested by those allocations.
+    pub bytes: u64,
+    /// Most bytes in use at once, above what was in use when the code started.
+    pub peak: u64,
+}
+
+/// Runs `f` and returns what it allocated. Counts are zero unless counting is enabled.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Usage) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+    let result = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes,
+        peak: (PEAK.load(Ordering::Relaxed) - current).max(0) as u64,
+    };
+    (result, usage)
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseUsage {
+    pub phase: Phase,
+    pub usage: Usage,
+}
+
+/// Result of [`profile`]: the answers and what every phase allocated.
+#[derive(Debug, Clone)]
+pub struct MemoryProfile {
+    pub answers: Vec<Answer>,
+    pub phases: Vec<PhaseUsage>,
+}
+
+/// Enables counting, then parses `data` and runs the requested parts once, measuring each phase.
+pub fn profile<S: Solution>(data: &str, parts: &[Part]) -> Result<MemoryProfile, Error> {
+    enable()?;
+    let (input, usage) = measure(|| S::parse(data));
+    let input = input?;
+    let mut phases = vec![PhaseUsage {
+        phase: Phase::Parse,
+        usage,
+    }];
+    let mut answers = Vec::new();
+    for part in parts {
+        let start = Instant::now();
+        let (value, usage) = measure(|| match part {
+            Part::One => S::part_one(&input).map(|answer| answer.to_string()),
+            Part::Two => S::part_two(&input).map(|answer| answer.to_string()),
+        });
+        answers.push(Answer {
+            part: *part,
+            value: value?,
+            duration: start.elapsed(),
+        });
+        phases.push(PhaseUsage {
+            phase: Phase::Part(*part),
+            usage,
+        });
+    }
+    Ok(MemoryProfile { answers, phases })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    struct Squares;
+
+    impl Solution for Squares {
+        type Input = Vec<u64>;
+
+        fn parse(data: &str) -> Result<Self::Input, Error> {
+            Ok((0..data.trim().parse().map_err(|_| Error::parse("Expected a count"))?).collect())
+        }
+
+        fn part_one(input: &Self::Input) -> Result<impl std::fmt::Display, Error> {
+            Ok(input.iter().map(|n| n * n).sum::<u64>())
+        }
+
+        fn part_two(input: &Self::Input) -> Result<impl std::fmt::Display, Error> {
+            let squares: Vec<u64> = input.iter().map(|n| n * n).collect();
+            Ok(squares.len())
+        }
+    }
+
+    // Other tests allocate at the same time, so the counts are only bounded from below, and
+    // measuring is kept to one test so that no other measurement resets the peak.
+    #[test]
+    fn test_profile() {
+        let profile = profile::<Squares>("100000", &Part::ALL).unwrap();
+        assert_eq!(profile.answers[0].value, "333328333350000");
+        assert_eq!(profile.answers[1].value, "100000");
+        let parse = profile.phases[0].usage;
+        assert!(parse.allocations >= 1);
+        assert!(parse.bytes >= 800_000);
+        assert!(parse.peak >= 800_000);
+        let part_two = profile.phases[2].usage;
+        assert!(part_two.bytes >= 800_000);
+        assert!(part_two.peak >= 800_000);
+
+        let (vec, usage) = measure(|| {
+            (0..100_u32).fold(Vec::new(), |mut vec, i| {
+                vec.push(i);
+                vec
+            })
+        });
+        assert_eq!(vec.len(), 100);
+        assert!(usage.allocations >= 2, "pushing reallocates: {:?}", usage);
+        assert!(usage.bytes >= 400);
+    }
+}
@@ -0,0 +1,387 @@
+//! A small parser-combinator toolkit for the line based puzzle formats.
+//!
+//! Parsers are functions from a [`Cursor`] to the parsed value and the cursor after it. Errors
+//! carry the columns they are about, so wrapping a line parse in [`parse_lines`](crate::parse_lines)
+//! is enough to point at the offending character. A line grammar reads like this:
+//!
+//! ```
+//! use common::parser::{integer, keyword, lexeme, many0, parse_all, preceded, tag, ws};
+//!
+//! let times = preceded((keyword("Time"), tag(":"), ws), many0(lexeme(integer::<i64>)));
+//! assert_eq!(parse_all(&times, "Time:      7  15   30").unwrap(), vec![7, 15, 30]);
+//! ```
+
+use std::{num::ParseIntError, ops::Range, str::FromStr};
+
+use crate::{Error, ErrorKind};
+
+/// A position inside the text being parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    /// The text not consumed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.src.len()
+    }
+
+    /// The 0-based character column.
+    pub fn column(&self) -> usize {
+        self.src[..self.pos].chars().count()
+    }
+
+    /// The character columns between this cursor and a later one.
+    pub fn columns_to(&self, end: &Cursor<'a>) -> Range<usize> {
+        self.column()..end.column()
+    }
+
+    fn advance(self, bytes: usize) -> Self {
+        Self {
+            src: self.src,
+            pos: self.pos + bytes,
+        }
+    }
+
+    /// A parse error pointing at the next character, e.g. `expected ':'`.
+    pub fn error(&self, message: impl Into<String>) -> Error {
+        let column = self.column();
+        Error::parse(message).with_columns(column..column + 1)
+    }
+
+    fn found(&self) -> String {
+        match self.rest().chars().next() {
+            Some(c) => format!("'{}'", c),
+            None => "end of line".to_string(),
+        }
+    }
+}
+
+pub type ParseResult<'a, T> = Result<(T, Cursor<'a>), Error>;
+
+/// Anything that can parse a `T` at a cursor; implemented for functions and closures.
+pub trait Parser<'a, T> {
+    fn parse(&self, cursor: Cursor<'a>) -> ParseResult<'a, T>;
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(Cursor<'a>) -> ParseResult<'a, T>,
+{
+    fn parse(&self, cursor: Cursor<'a>) -> ParseResult<'a, T> {
+        self(cursor)
+    }
+}
+
+macro_rules! tuple_parser {
+    ($($p:ident $t:ident),+) => {
+        /// A tuple of parsers runs them in sequence and returns all their values.
+        impl<'a, $($t, $p: Parser<'a, $t>),+> Parser<'a, ($($t,)+)> for ($($p,)+) {
+            #[allow(non_snake_case)]
+            fn parse(&self, cursor: Cursor<'a>) -> ParseResult<'a, ($($t,)+)> {
+                let ($($p,)+) = self;
+                $(let ($t, cursor) = $p.parse(cursor)?;)+
+                Ok((($($t,)+), cursor))
+            }
+        }
+    };
+}
+
+tuple_parser!(A TA, B TB);
+tuple_parser!(A TA, B TB, C TC);
+tuple_parser!(A TA, B TB, C TC, D TD);
+tuple_parser!(A TA, B TB, C TC, D TD, E TE);
+
+/// Skips spaces and tabs.
+pub fn ws(cursor: Cursor<'_>) -> ParseResult<'_, ()> {
+    let skipped = cursor.rest().len() - cursor.rest().trim_start_matches([' ', '\t']).len();
+    Ok(((), cursor.advance(skipped)))
+}
+
+/// Matches `text` literally.
+pub fn tag<'a>(text: &'static str) -> impl Fn(Cursor<'a>) -> ParseResult<'a, &'a str> {
+    move |cursor: Cursor<'a>| {
+        if cursor.rest().starts_with(text) {
+            Ok((&cursor.rest()[..text.len()], cursor.advance(text.len())))
+        } else {
+            Err(cursor.error(format!("expected '{}', found {}", text, cursor.found())))
+        }
+    }
+}
+
+/// Matches the word `word`, which must not continue with further letters or digits.
+pub fn keyword<'a>(word: &'static str) -> impl Fn(Cursor<'a>) -> ParseResult<'a, &'a str> {
+    move |cursor: Cursor<'a>| {
+        let rest = cursor.rest();
+        // Only a match guarantees that `word.len()` is a character boundary of `rest`
+        let matched = rest.starts_with(word) && rest[word.len()..].chars().next().is_none_or(|c| !c.is_alphanumeric());
+        if matched {
+            Ok((&rest[..word.len()], cursor.advance(word.len())))
+        } else {
+            let found: String = rest.chars().take_while(|c| c.is_alphanumeric()).collect();
+            let column = cursor.column();
+            Err(Error::parse(format!("expected '{}', found '{}'", word, found)).with_columns(column..column + found.chars().count().max(1)))
+        }
+    }
+}
+
+/// A run of ASCII letters.
+pub fn word(cursor: Cursor<'_>) -> ParseResult<'_, &str> {
+    let len = cursor.rest().len() - cursor.rest().trim_start_matches(|c: char| c.is_ascii_alphabetic()).len();
+    if len == 0 {
+        return Err(cursor.error(format!("expected a word, found {}", cursor.found())));
+    }
+    Ok((&cursor.rest()[..len], cursor.advance(len)))
+}
+
+/// An optionally negative decimal integer. Values that do not fit `T` are overflow errors.
+pub fn integer<T: FromStr<Err = ParseIntError>>(cursor: Cursor<'_>) -> ParseResult<'_, T> {
+    let rest = cursor.rest();
+    let sign = usize::from(rest.starts_with('-'));
+    let digits = rest[sign..].len() - rest[sign..].trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return Err(cursor.error(format!("expected a number, found {}", cursor.found())));
+    }
+    let end = cursor.advance(sign + digits);
+    let value = rest[..sign + digits]
+        .parse()
+        .map_err(|e| Error::from(e).with_columns(cursor.columns_to(&end)))?;
+    Ok((value, end))
+}
+
+/// `parser` followed by optional whitespace.
+pub fn lexeme<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, T> {
+    move |cursor| {
+        let (value, cursor) = parser.parse(cursor)?;
+        let ((), cursor) = ws(cursor)?;
+        Ok((value, cursor))
+    }
+}
+
+/// Applies `f` to the parsed value.
+pub fn map<'a, T, U>(parser: impl Parser<'a, T>, f: impl Fn(T) -> U) -> impl Fn(Cursor<'a>) -> ParseResult<'a, U> {
+    move |cursor| parser.parse(cursor).map(|(value, cursor)| (f(value), cursor))
+}
+
+/// Applies the fallible `f` to the parsed value; its errors point at the parsed text.
+pub fn try_map<'a, T, U>(parser: impl Parser<'a, T>, f: impl Fn(T) -> Result<U, Error>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, U> {
+    move |start: Cursor<'a>| {
+        let (value, end) = parser.parse(start)?;
+        let value = f(value).map_err(|e| e.with_columns(start.columns_to(&end)))?;
+        Ok((value, end))
+    }
+}
+
+/// Runs `second` after `first` and keeps the value of `second`.
+pub fn preceded<'a, T, U>(first: impl Parser<'a, T>, second: impl Parser<'a, U>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, U> {
+    move |cursor| {
+        let (_, cursor) = first.parse(cursor)?;
+        second.parse(cursor)
+    }
+}
+
+/// Runs `second` after `first` and keeps the value of `first`.
+pub fn terminated<'a, T, U>(first: impl Parser<'a, T>, second: impl Parser<'a, U>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, T> {
+    move |cursor| {
+        let (value, cursor) = first.parse(cursor)?;
+        let (_, cursor) = second.parse(cursor)?;
+        Ok((value, cursor))
+    }
+}
+
+/// Tries `first` and, if it fails, `second` from the same position. If both fail the error of the
+/// one that got further is returned.
+pub fn or<'a, T>(first: impl Parser<'a, T>, second: impl Parser<'a, T>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, T> {
+    move |cursor| match first.parse(cursor) {
+        Ok(result) => Ok(result),
+        Err(first_error) => second.parse(cursor).map_err(|second_error| {
+            if first_error.column() > second_error.column() {
+                first_error
+            } else {
+                second_error
+            }
+        }),
+    }
+}
+
+/// `Some` value if `parser` matches, `None` without consuming anything if it does not.
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, Option<T>> {
+    move |cursor| match parser.parse(cursor) {
+        Ok((value, cursor)) => Ok((Some(value), cursor)),
+        Err(_) => Ok((None, cursor)),
+    }
+}
+
+/// Applies `parser` as often as it matches, possibly never.
+pub fn many0<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, Vec<T>> {
+    move |cursor| Ok(repeat(&parser, cursor, Vec::new()))
+}
+
+/// Applies `parser` as often as it matches, at least once.
+pub fn many1<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, Vec<T>> {
+    move |cursor| {
+        let (first, cursor) = parser.parse(cursor)?;
+        Ok(repeat(&parser, cursor, vec![first]))
+    }
+}
+
+fn repeat<'a, T>(parser: &impl Parser<'a, T>, mut cursor: Cursor<'a>, mut values: Vec<T>) -> (Vec<T>, Cursor<'a>) {
+    while let Ok((value, next)) = parser.parse(cursor) {
+        if next == cursor {
+            // a parser that consumes nothing would match forever
+            break;
+        }
+        values.push(value);
+        cursor = next;
+    }
+    (values, cursor)
+}
+
+/// One or more `item`s separated by `separator`, e.g. `3 red, 4 blue`.
+pub fn separated1<'a, T, S>(item: impl Parser<'a, T>, separator: impl Parser<'a, S>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, Vec<T>> {
+    move |cursor| {
+        let (first, mut cursor) = item.parse(cursor)?;
+        let mut values = vec![first];
+        while let Ok((_, after_separator)) = separator.parse(cursor) {
+            // once a separator was seen the next item is mandatory
+            let (value, next) = item.parse(after_separator)?;
+            values.push(value);
+            cursor = next;
+        }
+        Ok((values, cursor))
+    }
+}
+
+/// Like [`separated1`], but also matches nothing.
+pub fn separated0<'a, T, S>(item: impl Parser<'a, T>, separator: impl Parser<'a, S>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, Vec<T>> {
+    let list = separated1(item, separator);
+    move |cursor| match list(cursor) {
+        Ok(result) => Ok(result),
+        Err(e) if e.column() == Some(cursor.column() + 1) => Ok((Vec::new(), cursor)),
+        Err(e) => Err(e),
+    }
+}
+
+/// The parsed value together with the character columns it covers.
+pub fn spanned<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, (T, Range<usize>)> {
+    move |start: Cursor<'a>| {
+        let (value, end) = parser.parse(start)?;
+        Ok(((value, start.columns_to(&end)), end))
+    }
+}
+
+/// Replaces the message of a parse error raised right at the start with `expected <what>`. Other
+/// errors, e.g. a number that overflows, are kept.
+pub fn expect<'a, T>(parser: impl Parser<'a, T>, what: &'static str) -> impl Fn(Cursor<'a>) -> ParseResult<'a, T> {
+    move |cursor: Cursor<'a>| {
+        parser.parse(cursor).map_err(|e| {
+            if e.kind() == ErrorKind::Parse && e.column() == Some(cursor.column() + 1) {
+                cursor.error(format!("expected {}, found {}", what, cursor.found()))
+            } else {
+                e
+            }
+        })
+    }
+}
+
+/// Succeeds only at the end of the text, after optional whitespace.
+pub fn end(cursor: Cursor<'_>) -> ParseResult<'_, ()> {
+    let ((), cursor) = ws(cursor)?;
+    if cursor.is_at_end() {
+        Ok(((), cursor))
+    } else {
+        Err(cursor.error(format!("expected end of line, found {}", cursor.found())))
+    }
+}
+
+/// Parses all of `text` with `parser`; trailing text other than whitespace is an error.
+pub fn parse_all<'a, T>(parser: &impl Parser<'a, T>, text: &'a str) -> Result<T, Error> {
+    let (value, cursor) = parser.parse(Cursor::new(text))?;
+    end(cursor)?;
+    Ok(value)
+}
+
+/// Parses every line with `parse` and keeps going after errors, so that all bad lines of an
+/// input are reported at once. Errors carry their line.
+pub fn recover_lines<T>(data: &str, mut parse: impl FnMut(&str) -> Result<T, Error>) -> (Vec<T>, Vec<Error>) {
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for (i, line) in data.lines().enumerate() {
+        match parse(line) {
+            Ok(value) => values.push(value),
+            Err(e) => errors.push(e.with_line(i + 1, line)),
+        }
+    }
+    (values, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_and_integer() {
+        let header = (keyword("Card"), ws, integer::<u32>, tag(":"));
+        let ((_, _, n, _), cursor) = header.parse(Cursor::new("Card 12: 41")).unwrap();
+        assert_eq!(n, 12);
+        assert_eq!(cursor.column(), 8);
+
+        let error = header.parse(Cursor::new("Cards 12:")).unwrap_err();
+        assert_eq!(error.column(), Some(1));
+        assert_eq!(error.message(), "expected 'Card', found 'Cards'");
+        let error = header.parse(Cursor::new("Caré 12:")).unwrap_err();
+        assert_eq!(error.message(), "expected 'Card', found 'Caré'");
+        assert_eq!(error.column(), Some(1));
+        assert!(keyword("Card").parse(Cursor::new("Cardé")).is_err());
+
+        assert_eq!(parse_all(&integer::<i64>, "-42").unwrap(), -42);
+        let error = parse_all(&integer::<u8>, "300").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_separated() {
+        let cube = (lexeme(integer::<u16>), word);
+        let reveal = separated1(cube, (tag(","), ws));
+        let reveals = separated1(reveal, (tag(";"), ws));
+        let parsed = parse_all(&reveals, "3 blue, 4 red; 1 red").unwrap();
+        assert_eq!(parsed, vec![vec![(3, "blue"), (4, "red")], vec![(1, "red")]]);
+
+        let error = parse_all(&reveals, "3 blue, red").unwrap_err();
+        assert_eq!(error.column(), Some(9));
+        assert_eq!(parse_all(&separated0(integer::<u8>, tag(",")), "").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_or_many_spanned() {
+        let token = or(map(keyword("Time"), |_| 0), map(keyword("Distance"), |_| 1));
+        assert_eq!(parse_all(&token, "Distance").unwrap(), 1);
+        let numbers = many1(lexeme(spanned(integer::<i64>)));
+        assert_eq!(parse_all(&numbers, "7  15").unwrap(), vec![(7, 0..1), (15, 3..5)]);
+        let error = parse_all(&numbers, "7 1x").unwrap_err();
+        assert_eq!(error.column(), Some(4));
+        assert!(parse_all(&expect(integer::<i64>, "a race time"), "x")
+            .unwrap_err()
+            .message()
+            .starts_with("expected a race time"));
+        let error = parse_all(&expect(integer::<u8>, "a count"), "300").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_recover_lines() {
+        let (values, errors) = recover_lines("1\nx\n3\n-", |line| parse_all(&integer::<i32>, line));
+        assert_eq!(values, vec![1, 3]);
+        assert_eq!(errors.iter().map(|e| e.line()).collect::<Vec<_>>(), vec![Some(2), Some(4)]);
+    }
+}
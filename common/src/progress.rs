@@ -0,0 +1,402 @@
+//! Progress of long-running parts.
+//!
+//! Workers report the units of work they finish to a [`Bar`] of a [`Progress`]. On a terminal,
+//! every bar and the total are redrawn in place with their throughput and ETA. Otherwise, e.g.
+//! when stderr goes to a file or to `advoc watch`, the total is logged every few seconds and
+//! every bar once it is done.
+
+use std::{
+    env,
+    fmt::Write as _,
+    io::{self, IsTerminal, Write as _},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tracing::info;
+
+/// Environment variable forcing the display: `bars`, `log` or `off`.
+pub const PROGRESS_ENV: &str = "ADVOC_PROGRESS";
+
+/// How often the bars are redrawn.
+const TICK: Duration = Duration::from_millis(100);
+
+/// How often the total is logged when there are no bars.
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+const BAR_WIDTH: usize = 30;
+
+/// How progress is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Bars redrawn in place, for a terminal.
+    Bars,
+    /// Periodic log lines.
+    Log,
+    /// Nothing at all.
+    Off,
+}
+
+impl Mode {
+    /// The mode named by `$ADVOC_PROGRESS`, or bars if stderr is a terminal and log lines if not.
+    pub fn detect() -> Self {
+        match env::var(PROGRESS_ENV).as_deref() {
+            Ok("bars") => Mode::Bars,
+            Ok("log") => Mode::Log,
+            Ok("off") => Mode::Off,
+            _ if io::stderr().is_terminal() => Mode::Bars,
+            _ => Mode::Log,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BarState {
+    name: String,
+    total: u64,
+    /// Work that was done before this run, e.g. restored from a checkpoint; it does not count
+    /// towards the throughput.
+    initial: AtomicU64,
+    position: AtomicU64,
+    finished: AtomicBool,
+    started: Instant,
+    mode: Mode,
+}
+
+impl BarState {
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            name: self.name.clone(),
+            position: self.position.load(Ordering::Relaxed).min(self.total),
+            total: self.total,
+            initial: self.initial.load(Ordering::Relaxed),
+            elapsed: self.started.elapsed(),
+            finished: self.finished.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// One unit of the work, e.g. one range searched by one task.
+#[derive(Debug, Clone)]
+pub struct Bar {
+    state: Arc<BarState>,
+}
+
+impl Bar {
+    /// Starts the bar at `position`, for work that was partly done before.
+    pub fn with_position(self, position: u64) -> Self {
+        self.state.initial.store(position, Ordering::Relaxed);
+        self.state.position.store(position, Ordering::Relaxed);
+        self
+    }
+
+    /// Reports `units` more of finished work.
+    pub fn inc(&self, units: u64) {
+        self.state.position.fetch_add(units, Ordering::Relaxed);
+    }
+
+    /// Marks the bar as done, even if it stopped short of its total.
+    pub fn finish(&self) {
+        if !self.state.finished.swap(true, Ordering::Relaxed) && self.state.mode == Mode::Log {
+            let snapshot = self.state.snapshot();
+            info!(bar = %snapshot.name, done = snapshot.position, total = snapshot.total, elapsed = ?snapshot.elapsed, "Finished");
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    unit: String,
+    bars: Mutex<Vec<Arc<BarState>>>,
+    started: Instant,
+}
+
+impl Shared {
+    fn snapshots(&self) -> Vec<Snapshot> {
+        let bars = self.bars.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        bars.iter().map(|bar| bar.snapshot()).collect()
+    }
+
+    /// The bars followed by their total.
+    fn lines(&self) -> Vec<String> {
+        let bars = self.snapshots();
+        let total = Snapshot::total(&bars, self.started.elapsed());
+        let width = bars.iter().map(|bar| bar.name.len()).chain([total.name.len()]).max().unwrap_or(0);
+        bars.iter().chain([&total]).map(|bar| bar.line(width, &self.unit)).collect()
+    }
+
+    fn log(&self) {
+        let total = Snapshot::total(&self.snapshots(), self.started.elapsed());
+        info!(
+            done = total.position,
+            total = total.total,
+            percent = format!("{:.1}", total.percent()),
+            rate = %total.rate_text(&self.unit),
+            eta = %total.eta_text(),
+            "Progress"
+        );
+    }
+}
+
+/// A live display of the progress of a part, drawn from a background thread until it is
+/// finished or dropped.
+#[derive(Debug)]
+pub struct Progress {
+    shared: Arc<Shared>,
+    mode: Mode,
+    display: Option<(mpsc::Sender<()>, JoinHandle<()>)>,
+}
+
+impl Progress {
+    /// Shows the progress as [`Mode::detect`] says; `unit` names what the work is counted in,
+    /// e.g. `seeds`.
+    pub fn new(unit: &str) -> Self {
+        Self::with_mode(unit, Mode::detect())
+    }
+
+    pub fn with_mode(unit: &str, mode: Mode) -> Self {
+        let shared = Arc::new(Shared {
+            unit: unit.to_string(),
+            bars: Mutex::new(Vec::new()),
+            started: Instant::now(),
+        });
+        let display = match mode {
+            Mode::Off => None,
+            Mode::Bars | Mode::Log => {
+                let (stop, stopped) = mpsc::channel();
+                let shared = Arc::clone(&shared);
+                Some((stop, thread::spawn(move || display(&shared, mode, &stopped))))
+            }
+        };
+        Self { shared, mode, display }
+    }
+
+    /// Adds a bar for `total` units of work.
+    pub fn bar(&self, name: impl Into<String>, total: u64) -> Bar {
+        let state = Arc::new(BarState {
+            name: name.into(),
+            total,
+            initial: AtomicU64::new(0),
+            position: AtomicU64::new(0),
+            finished: AtomicBool::new(false),
+            started: Instant::now(),
+            mode: self.mode,
+        });
+        let mut bars = self.shared.bars.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        bars.push(Arc::clone(&state));
+        Bar { state }
+    }
+
+    /// Stops the display after drawing, or logging, the final state.
+    pub fn finish(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        if let Some((stop, display)) = self.display.take() {
+            let _ = stop.send(());
+            let _ = display.join();
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Redraws the bars every tick, or logs the total every interval, until told to stop.
+fn display(shared: &Shared, mode: Mode, stopped: &mpsc::Receiver<()>) {
+    let tick = if mode == Mode::Bars { TICK } else { LOG_INTERVAL };
+    let mut drawn = 0;
+    loop {
+        let stop = !matches!(stopped.recv_timeout(tick), Err(RecvTimeoutError::Timeout));
+        match mode {
+            Mode::Bars => drawn = draw(&shared.lines(), drawn),
+            Mode::Log => shared.log(),
+            Mode::Off => {}
+        }
+        if stop {
+            break;
+        }
+    }
+}
+
+/// Overwrites the `drawn` lines of the last draw with `lines`; returns how many were drawn.
+fn draw(lines: &[String], drawn: usize) -> usize {
+    let mut out = String::new();
+    if drawn > 0 {
+        let _ = write!(out, "\x1b[{}A", drawn);
+    }
+    for line in lines {
+        let _ = writeln!(out, "\r\x1b[2K{}", line);
+    }
+    let _ = io::stderr().lock().write_all(out.as_bytes());
+    lines.len()
+}
+
+/// The state of a bar at one moment.
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    name: String,
+    position: u64,
+    total: u64,
+    initial: u64,
+    elapsed: Duration,
+    finished: bool,
+}
+
+impl Snapshot {
+    fn total(bars: &[Snapshot], elapsed: Duration) -> Self {
+        Snapshot {
+            name: "total".to_string(),
+            position: bars.iter().map(|bar| bar.position).sum(),
+            total: bars.iter().map(|bar| bar.total).sum(),
+            initial: bars.iter().map(|bar| bar.initial).sum(),
+            elapsed,
+            finished: !bars.is_empty() && bars.iter().all(|bar| bar.finished),
+        }
+    }
+
+    fn percent(&self) -> f64 {
+        match self.total {
+            0 => 100.0,
+            total => self.position as f64 * 100.0 / total as f64,
+        }
+    }
+
+    /// Units per second done in this run.
+    fn rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.position.saturating_sub(self.initial) as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    fn rate_text(&self, unit: &str) -> String {
+        format!("{} {}/s", si(self.rate()), unit)
+    }
+
+    fn eta_text(&self) -> String {
+        let rate = self.rate();
+        if self.finished || self.position >= self.total {
+            "done".to_string()
+        } else if rate == 0.0 {
+            "?".to_string()
+        } else {
+            duration(Duration::from_secs_f64((self.total - self.position) as f64 / rate))
+        }
+    }
+
+    fn line(&self, width: usize, unit: &str) -> String {
+        let filled = (self.percent() / 100.0 * BAR_WIDTH as f64) as usize;
+        format!(
+            "{:<width$} [{}{}] {:>5.1}% {:>7}/{:<7} {:>14} ETA {}",
+            self.name,
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            self.percent(),
+            si(self.position as f64),
+            si(self.total as f64),
+            self.rate_text(unit),
+            self.eta_text(),
+            width = width
+        )
+    }
+}
+
+/// `value` with a metric suffix, e.g. `1.81G`.
+fn si(value: f64) -> String {
+    const SUFFIXES: [&str; 4] = ["K", "M", "G", "T"];
+    if value < 1000.0 {
+        return format!("{}", value.round());
+    }
+    let mut scaled = value;
+    let mut suffix = "";
+    for next in SUFFIXES {
+        if scaled < 1000.0 {
+            break;
+        }
+        scaled /= 1000.0;
+        suffix = next;
+    }
+    format!("{:.2}{}", scaled, suffix)
+}
+
+/// A duration rounded to seconds, e.g. `1h02m`, `3m05s` or `12s`.
+fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m{:02}s", m, s),
+        (h, m, _) => format!("{}h{:02}m", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(position: u64, total: u64, initial: u64, secs: u64) -> Snapshot {
+        Snapshot {
+            name: "range 1".to_string(),
+            position,
+            total,
+            initial,
+            elapsed: Duration::from_secs(secs),
+            finished: false,
+        }
+    }
+
+    #[test]
+    fn test_line() {
+        assert_eq!(
+            snapshot(2_500_000, 10_000_000, 500_000, 4).line(8, "seeds"),
+            "range 1  [#######-----------------------]  25.0%   2.50M/10.00M  500.00K seeds/s ETA 15s"
+        );
+        assert!(snapshot(0, 10, 0, 4).line(8, "seeds").ends_with("0 seeds/s ETA ?"));
+        let finished = Snapshot {
+            finished: true,
+            ..snapshot(5, 10, 0, 1)
+        };
+        assert!(finished.line(8, "seeds").ends_with("ETA done"));
+    }
+
+    #[test]
+    fn test_total() {
+        let total = Snapshot::total(&[snapshot(5, 10, 0, 1), snapshot(10, 30, 5, 2)], Duration::from_secs(5));
+        assert_eq!((total.position, total.total, total.initial), (15, 40, 5));
+        assert_eq!(total.rate(), 2.0);
+        assert_eq!(total.eta_text(), "12s");
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(si(999.0), "999");
+        assert_eq!(si(1_809_081_164.0), "1.81G");
+        assert_eq!(duration(Duration::from_secs(185)), "3m05s");
+        assert_eq!(duration(Duration::from_secs(3725)), "1h02m");
+    }
+
+    #[test]
+    fn test_bars() {
+        let progress = Progress::with_mode("seeds", Mode::Off);
+        let bar = progress.bar("range 1", 100).with_position(40);
+        bar.inc(10);
+        bar.inc(60);
+        bar.finish();
+        let lines = progress.shared.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("range 1 [##############################] 100.0%     100/100"));
+        assert!(lines[1].starts_with("total   ["));
+        progress.finish();
+    }
+}
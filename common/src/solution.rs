@@ -0,0 +1,100 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use tracing::info_span;
+
+use crate::Error;
+
+/// One of the two puzzle parts of a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = Error;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(Error::validation(format!("There is no part {}", v))),
+        }
+    }
+}
+
+/// A timed step of solving a day: parsing the input or running one part on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    Parse,
+    Part(Part),
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+/// The rendered answer of one part and how long the part took to compute it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub part: Part,
+    pub value: String,
+    pub duration: Duration,
+}
+
+/// A day's puzzle. The input is parsed once and then handed to both parts.
+pub trait Solution {
+    type Input;
+
+    fn parse(data: &str) -> Result<Self::Input, Error>;
+
+    fn part_one(input: &Self::Input) -> Result<impl Display, Error>;
+
+    fn part_two(input: &Self::Input) -> Result<impl Display, Error>;
+}
+
+/// Parse `data` and run the requested parts, returning the rendered answers in order.
+///
+/// Every phase runs inside a `phase` span, so the telemetry subscriber can time it.
+pub fn solve<S: Solution>(data: &str, parts: &[Part]) -> Result<Vec<Answer>, Error> {
+    let input = info_span!("phase", phase = %Phase::Parse).in_scope(|| S::parse(data))?;
+    let mut answers = Vec::new();
+    for part in parts {
+        let start = Instant::now();
+        let value = info_span!("phase", phase = %Phase::Part(*part)).in_scope(|| match part {
+            Part::One => S::part_one(&input).map(|answer| answer.to_string()),
+            Part::Two => S::part_two(&input).map(|answer| answer.to_string()),
+        })?;
+        answers.push(Answer {
+            part: *part,
+            value,
+            duration: start.elapsed(),
+        });
+    }
+    Ok(answers)
+}
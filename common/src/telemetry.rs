@@ -0,0 +1,78 @@
+//! One tracing subscriber for every day.
+//!
+//! Logs go to stderr so that answers on stdout stay machine readable. Events of the `log` crate
+//! are forwarded to the same subscriber.
+
+use std::io;
+
+use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+use crate::Error;
+
+/// Filter used when neither the command line nor `RUST_LOG` set one.
+pub const DEFAULT_FILTER: &str = "info";
+
+/// Configuration of the subscriber installed by [`Telemetry::init`].
+#[derive(Debug, Clone, Default)]
+pub struct Telemetry {
+    filter: Option<String>,
+    json: bool,
+    span_timing: bool,
+}
+
+impl Telemetry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An `EnvFilter` directive like `debug` or `day05=trace`. Takes precedence over `RUST_LOG`.
+    pub fn with_filter(mut self, filter: Option<&str>) -> Self {
+        self.filter = filter.map(str::to_string);
+        self
+    }
+
+    /// Emit one JSON object per line instead of human readable text.
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Log every closed span together with its busy and idle time, e.g. each `phase` span of
+    /// [`solve`](crate::solve).
+    pub fn with_span_timing(mut self, span_timing: bool) -> Self {
+        self.span_timing = span_timing;
+        self
+    }
+
+    /// Installs the subscriber globally. Fails if a subscriber is already installed.
+    pub fn init(self) -> Result<(), Error> {
+        let filter = match &self.filter {
+            Some(filter) => {
+                EnvFilter::try_new(filter).map_err(|e| Error::parse(format!("Invalid log filter '{}'", filter)).with_source(e))?
+            }
+            None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER)),
+        };
+        let span_events = if self.span_timing { FmtSpan::CLOSE } else { FmtSpan::NONE };
+        let builder = tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(io::stderr)
+            .with_span_events(span_events)
+            .with_thread_ids(true)
+            .with_line_number(true);
+
+        // `try_init` also installs the `log` bridge.
+        let installed = if self.json { builder.json().try_init() } else { builder.try_init() };
+        installed.map_err(|e| Error::other(format!("Could not install the tracing subscriber: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_filter() {
+        let error = Telemetry::new().with_filter(Some("day05=[")).init().unwrap_err();
+        assert_eq!(error.kind(), crate::ErrorKind::Parse);
+    }
+}
@@ -0,0 +1,375 @@
+//! How likely the reveals of a game are with a bag, to estimate what the elf put in it.
+//!
+//! Every reveal is a handful of cubes drawn from the bag and put back before the next one, so the
+//! reveals of a game are independent. Within a reveal the cubes are drawn one at a time, either
+//! putting each back before drawing the next or not, see [`Model`].
+
+use common::Error;
+
+use crate::game::{Bag, CubeColor, Game};
+
+/// The most bags [`Model::maximum_likelihood`] and [`Model::posterior_of`] weigh. The number of
+/// bags grows with the size of the bag to the power of the number of colors less one, so larger
+/// sizes or palettes are rejected rather than left running for hours.
+pub const MAX_BAGS: u64 = 10_000_000;
+
+/// How the cubes of one reveal are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// Each cube is put back before the next is drawn: a multinomial draw.
+    WithReplacement,
+    /// No cube is drawn twice within a reveal: a multivariate hypergeometric draw.
+    WithoutReplacement,
+}
+
+/// The reveals of a game as counts of a list of colors.
+struct Observations {
+    colors: Vec<CubeColor>,
+    reveals: Vec<Vec<u16>>,
+    /// `ln(n!)` for every `n` up to the largest bag or reveal asked about.
+    ln_factorials: Vec<f64>,
+}
+
+impl Observations {
+    /// Fails if the game shows a color that is not in `colors`, as no bag of them explains it.
+    fn new(game: &Game, colors: Vec<CubeColor>, largest_bag: u32) -> Result<Self, Error> {
+        if let Some((color, _)) = game.minimal_bag().cubes().find(|(color, _)| !colors.contains(color)) {
+            return Err(Error::validation(format!(
+                "Game {} shows {} cubes, which no bag holds",
+                game.id, color
+            )));
+        }
+        let reveals: Vec<Vec<u16>> = game
+            .reveals()
+            .iter()
+            .map(|reveal| colors.iter().map(|color| reveal.count(color)).collect())
+            .collect();
+        let largest_reveal = reveals.iter().map(|reveal| total(reveal)).max().unwrap_or(0);
+        let ln_factorials = (1..=largest_bag.max(largest_reveal))
+            .scan(0.0, |ln_factorial, n| {
+                *ln_factorial += f64::from(n).ln();
+                Some(*ln_factorial)
+            })
+            .collect();
+        Ok(Self {
+            colors,
+            reveals,
+            ln_factorials,
+        })
+    }
+
+    fn ln_factorial(&self, n: u32) -> f64 {
+        n.checked_sub(1).map_or(0.0, |i| self.ln_factorials[i as usize])
+    }
+
+    fn ln_choose(&self, n: u32, k: u32) -> f64 {
+        self.ln_factorial(n) - self.ln_factorial(k) - self.ln_factorial(n - k)
+    }
+
+    /// The natural logarithm of the probability of the reveals with `bag`, in the order of
+    /// `colors`; minus infinity if they are impossible.
+    fn log_likelihood(&self, model: Model, bag: &[u16]) -> f64 {
+        let size = total(bag);
+        let mut log_likelihood = 0.0;
+        for reveal in &self.reveals {
+            let drawn = total(reveal);
+            match model {
+                Model::WithReplacement => {
+                    log_likelihood += self.ln_factorial(drawn);
+                    for (held, shown) in bag.iter().zip(reveal).filter(|(_, shown)| **shown > 0) {
+                        if *held == 0 {
+                            return f64::NEG_INFINITY;
+                        }
+                        log_likelihood += f64::from(*shown) * (f64::from(*held) / f64::from(size)).ln();
+                        log_likelihood -= self.ln_factorial(u32::from(*shown));
+                    }
+                }
+                Model::WithoutReplacement => {
+                    if drawn > size {
+                        return f64::NEG_INFINITY;
+                    }
+                    log_likelihood -= self.ln_choose(size, drawn);
+                    for (held, shown) in bag.iter().zip(reveal) {
+                        if shown > held {
+                            return f64::NEG_INFINITY;
+                        }
+                        log_likelihood += self.ln_choose(u32::from(*held), u32::from(*shown));
+                    }
+                }
+            }
+        }
+        log_likelihood
+    }
+
+    fn counts(&self, bag: &Bag) -> Vec<u16> {
+        self.colors.iter().map(|color| bag.count(color)).collect()
+    }
+
+    fn bag(&self, counts: &[u16]) -> Bag {
+        Bag::new(self.colors.iter().cloned().zip(counts.iter().copied()))
+    }
+}
+
+impl Model {
+    /// The natural logarithm of the probability of the reveals of `game` if the cubes were drawn
+    /// from `bag`; minus infinity if they could not have been.
+    pub fn log_likelihood(self, game: &Game, bag: &Bag) -> f64 {
+        let mut colors: Vec<CubeColor> = bag.cubes().map(|(color, _)| color.clone()).collect();
+        colors.extend(game.minimal_bag().cubes().map(|(color, _)| color.clone()));
+        colors.sort_unstable();
+        colors.dedup();
+        Observations::new(game, colors, bag.total()).map_or(f64::NEG_INFINITY, |observations| {
+            observations.log_likelihood(self, &observations.counts(bag))
+        })
+    }
+
+    /// The bag of `size` cubes that makes the reveals of `game` most likely, or none if no bag
+    /// that small explains them. It only holds colors the game shows, since cubes of any other
+    /// color make every reveal less likely. Fails if no bag of those colors holds `size` cubes or
+    /// there are more than [`MAX_BAGS`] to weigh.
+    pub fn maximum_likelihood(self, game: &Game, size: u32) -> Result<Option<Bag>, Error> {
+        let shown = game.minimal_bag();
+        let least: Vec<u16> = match self {
+            Model::WithReplacement => vec![1; shown.cubes().count()],
+            Model::WithoutReplacement => shown.cubes().map(|(_, count)| count).collect(),
+        };
+        check_bag_count(&least, size)?;
+        let colors: Vec<CubeColor> = shown.cubes().map(|(color, _)| color.clone()).collect();
+        let observations = Observations::new(game, colors, size)?;
+        let mut best: Option<(f64, Vec<u16>)> = None;
+        compositions(&least, size, &mut |bag| {
+            let log_likelihood = observations.log_likelihood(self, bag);
+            if log_likelihood > best.as_ref().map_or(f64::NEG_INFINITY, |(best, _)| *best) {
+                best = Some((log_likelihood, bag.to_vec()));
+            }
+        });
+        Ok(best.map(|(_, bag)| observations.bag(&bag)))
+    }
+
+    /// The probability that the elf used each of `candidates` given the reveals of `game`, all
+    /// candidates being equally likely beforehand. All zero if none explains the reveals.
+    pub fn posterior(self, game: &Game, candidates: &[Bag]) -> Vec<f64> {
+        normalize(candidates.iter().map(|bag| self.log_likelihood(game, bag)).collect())
+    }
+
+    /// The probability that the elf used `bag` given the reveals of `game`, every bag of the same
+    /// size holding only `colors` being equally likely beforehand. Fails if there are more than
+    /// [`MAX_BAGS`] such bags.
+    pub fn posterior_of(self, game: &Game, bag: &Bag, colors: &[CubeColor]) -> Result<f64, Error> {
+        if let Some((color, _)) = bag.cubes().find(|(color, _)| !colors.contains(color)) {
+            return Err(Error::validation(format!(
+                "The bag holds {} cubes, which is not one of the colors",
+                color
+            )));
+        }
+        check_bag_count(&vec![0; colors.len()], bag.total())?;
+        let observations = Observations::new(game, colors.to_vec(), bag.total())?;
+        let candidate = observations.log_likelihood(self, &observations.counts(bag));
+        let mut log_likelihoods = Vec::new();
+        compositions(&vec![0; colors.len()], bag.total(), &mut |other| {
+            log_likelihoods.push(observations.log_likelihood(self, other));
+        });
+        let evidence = log_sum_exp(&log_likelihoods);
+        Ok(if candidate == f64::NEG_INFINITY {
+            0.0
+        } else {
+            (candidate - evidence).exp()
+        })
+    }
+}
+
+fn total(counts: &[u16]) -> u32 {
+    counts.iter().map(|count| u32::from(*count)).sum()
+}
+
+/// Calls `f` with every way to hold `size` cubes, at least `least` of each color.
+fn compositions(least: &[u16], size: u32, f: &mut impl FnMut(&[u16])) {
+    fn fill(least: &[u16], left: u32, bag: &mut Vec<u16>, f: &mut impl FnMut(&[u16])) {
+        let color = bag.len();
+        if color + 1 == least.len() {
+            if let Ok(count) = u16::try_from(left) {
+                bag.push(count);
+                f(bag);
+                bag.pop();
+            }
+            return;
+        }
+        let rest = total(&least[color + 1..]);
+        for count in u32::from(least[color])..=left.saturating_sub(rest).min(u32::from(u16::MAX)) {
+            bag.push(count as u16);
+            fill(least, left - count, bag, f);
+            bag.pop();
+        }
+    }
+    if !least.is_empty() && total(least) <= size {
+        fill(least, size, &mut Vec::with_capacity(least.len()), f);
+    }
+}
+
+/// Fails if a bag of `least.len()` colors, holding at most `u16::MAX` cubes of each, cannot hold
+/// `size` cubes, or if there are more than [`MAX_BAGS`] ways to hold them, at least `least` of
+/// each color.
+fn check_bag_count(least: &[u16], size: u32) -> Result<(), Error> {
+    let most = least.len() as u64 * u64::from(u16::MAX);
+    if u64::from(size) > most {
+        return Err(Error::validation(format!(
+            "A bag of {} colors holds at most {} cubes, not {}",
+            least.len(),
+            most,
+            size
+        )));
+    }
+    let Some(spare) = size.checked_sub(total(least)) else {
+        return Ok(());
+    };
+    // Ways to spread the spare cubes over the colors: spare + colors - 1 choose colors - 1
+    let mut bags: u128 = 1;
+    for i in 1..least.len() as u128 {
+        bags = bags * (u128::from(spare) + i) / i;
+        if bags > u128::from(MAX_BAGS) {
+            return Err(Error::validation(format!(
+                "There are too many bags of {} cubes of {} colors to weigh, at most {} are",
+                size,
+                least.len(),
+                MAX_BAGS
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// `ln(sum(exp(value)))`, without the exponentials overflowing or all rounding to zero.
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let most = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if most == f64::NEG_INFINITY {
+        return f64::NEG_INFINITY;
+    }
+    most + values.iter().map(|value| (value - most).exp()).sum::<f64>().ln()
+}
+
+/// Probabilities proportional to the exponentials of `log_likelihoods`.
+fn normalize(log_likelihoods: Vec<f64>) -> Vec<f64> {
+    let evidence = log_sum_exp(&log_likelihoods);
+    log_likelihoods
+        .iter()
+        .map(|log_likelihood| {
+            if evidence == f64::NEG_INFINITY {
+                0.0
+            } else {
+                (log_likelihood - evidence).exp()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bag(text: &str) -> Bag {
+        text.parse().unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} is not {}", actual, expected);
+    }
+
+    #[test]
+    fn test_likelihood() {
+        let game = Game::parse_game("Game 1: 1 red, 1 blue; 2 red").unwrap();
+        // Both reveals draw one of the six pairs of four cubes
+        assert_close(
+            Model::WithoutReplacement.log_likelihood(&game, &bag("3 red, 1 blue")).exp(),
+            0.5 * 0.5,
+        );
+        assert_close(
+            Model::WithReplacement.log_likelihood(&game, &bag("3 red, 1 blue")).exp(),
+            0.375 * 0.5625,
+        );
+        assert_eq!(
+            Model::WithoutReplacement.log_likelihood(&game, &bag("1 red, 3 blue")),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(
+            Model::WithReplacement.log_likelihood(&game, &bag("4 red, 3 green")),
+            f64::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn test_maximum_likelihood() {
+        let game = Game::parse_game("Game 1: 1 red, 1 blue; 2 red").unwrap();
+        for model in [Model::WithReplacement, Model::WithoutReplacement] {
+            assert_eq!(
+                model.maximum_likelihood(&game, 4).unwrap(),
+                Some(bag("3 red, 1 blue")),
+                "{:?}",
+                model
+            );
+        }
+        assert_eq!(Model::WithoutReplacement.maximum_likelihood(&game, 2).unwrap(), None);
+
+        // Drawing with replacement, the bag is the shown cubes when it has as many
+        let game = Game::parse_game("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        assert_eq!(
+            Model::WithReplacement.maximum_likelihood(&game, 18).unwrap(),
+            Some(bag("5 red, 4 green, 9 blue"))
+        );
+        let estimate = Model::WithoutReplacement.maximum_likelihood(&game, 18).unwrap().unwrap();
+        assert!(game.is_valid(&estimate));
+        assert_eq!(estimate.total(), 18);
+    }
+
+    #[test]
+    fn test_posterior() {
+        let game = Game::parse_game("Game 1: 1 red, 1 blue; 2 red").unwrap();
+        let candidates = [bag("3 red, 1 blue"), bag("2 red, 2 blue"), bag("4 red")];
+        let posterior = Model::WithoutReplacement.posterior(&game, &candidates);
+        for (actual, expected) in posterior.iter().zip([9.0 / 13.0, 4.0 / 13.0, 0.0]) {
+            assert_close(*actual, expected);
+        }
+        let posterior = Model::WithReplacement.posterior(&game, &candidates);
+        for (actual, expected) in posterior.iter().zip([27.0 / 43.0, 16.0 / 43.0, 0.0]) {
+            assert_close(*actual, expected);
+        }
+        assert_eq!(Model::WithReplacement.posterior(&game, &[bag("4 red")]), vec![0.0]);
+
+        // Of the other bags of four red or blue cubes, only 2 red and 2 blue explains the reveals,
+        // with likelihood 4/6 * 1/6 against 1/2 * 1/2
+        let colors = [CubeColor::new("red"), CubeColor::new("blue")];
+        assert_close(
+            Model::WithoutReplacement.log_likelihood(&game, &bag("2 red, 2 blue")).exp(),
+            4.0 / 36.0,
+        );
+        let probability = Model::WithoutReplacement
+            .posterior_of(&game, &bag("3 red, 1 blue"), &colors)
+            .unwrap();
+        assert_close(probability, 9.0 / 13.0);
+        assert!(Model::WithoutReplacement.posterior_of(&game, &bag("4 green"), &colors).is_err());
+        assert!(Model::WithoutReplacement.posterior_of(&game, &bag("4 red"), &colors[..1]).is_err());
+    }
+    #[test]
+    fn test_too_many_bags() {
+        let game = Game::parse_game("Game 1: 1 red, 1 blue; 2 red").unwrap();
+        assert!(Model::WithReplacement.maximum_likelihood(&game, 100_000).is_ok());
+        let colors: Vec<CubeColor> = ["red", "green", "blue", "yellow"].map(CubeColor::new).to_vec();
+        let error = Model::WithReplacement
+            .posterior_of(&game, &bag("600 red, 1 blue"), &colors)
+            .unwrap_err();
+        assert_eq!(
+            error.message(),
+            "There are too many bags of 601 cubes of 4 colors to weigh, at most 10000000 are"
+        );
+        let game = Game::parse_game("Game 1: 1 red, 1 green, 1 blue").unwrap();
+        assert!(Model::WithReplacement.maximum_likelihood(&game, 10_000).is_err());
+
+        // Counts are u16, so a bag of one color holds at most 65535 cubes
+        let game = Game::parse_game("Game 1: 2 red; 1 red").unwrap();
+        assert_eq!(
+            Model::WithReplacement.maximum_likelihood(&game, 65535).unwrap(),
+            Some(bag("65535 red"))
+        );
+        let error = Model::WithReplacement.maximum_likelihood(&game, u32::MAX).unwrap_err();
+        assert_eq!(error.message(), "A bag of 1 colors holds at most 65535 cubes, not 4294967295");
+    }
+}
@@ -1,7 +1,59 @@
-use std::{collections::HashMap, error::Error, fmt, result::Result, str::FromStr};
-use strum_macros::{EnumString, Display};
+use common::{
+    parser::{expect, integer, keyword, lexeme, map, parse_all, separated1, spanned, tag, word, ws, Cursor, ParseResult, Parser},
+    Error,
+};
+use std::{collections::BTreeMap, fmt, ops::Range, result::Result, str::FromStr};
 
-#[derive(Debug)]
+/// The bag part one asks about, unless the input declares another.
+pub const PUZZLE_BAG: &str = "12 red, 13 green, 14 blue";
+
+/// The puzzle input: the games and the cubes they are played with.
+#[derive(Debug, Clone)]
+pub struct Games {
+    pub palette: Palette,
+    /// The bag of part one.
+    pub bag: Bag,
+    pub games: Vec<Game>,
+}
+
+impl Games {
+    // The games may be preceded by headers declaring the colors and the bag of part one:
+    //
+    //   Colors: red, green, blue, yellow, black
+    //   Bag: 12 red, 13 green, 14 blue, 3 yellow, 2 black
+    //
+    // Without a `Colors` header the palette is red, green, blue and any other color shown.
+    pub fn parse(data: &str) -> Result<Games, Error> {
+        let mut palette = Palette::standard();
+        let mut bag = None;
+        let mut games = Vec::new();
+        for (i, line) in data.lines().enumerate() {
+            let parsed = if line.starts_with("Colors") {
+                if palette.is_declared() || bag.is_some() || !games.is_empty() {
+                    Err(Error::parse("The colors must be declared once, before the bag and the games"))
+                } else {
+                    parse_colors(line).map(|colors| palette = Palette::declared(colors))
+                }
+            } else if line.starts_with("Bag") {
+                if bag.is_some() || !games.is_empty() {
+                    Err(Error::parse("The bag must be declared once, before the games"))
+                } else {
+                    parse_bag(line, &mut palette).map(|declared| bag = Some(declared))
+                }
+            } else {
+                Game::parse_with_palette(line, &mut palette).map(|game| games.push(game))
+            };
+            parsed.map_err(|e| e.with_line(i + 1, line))?;
+        }
+        let bag = match bag {
+            Some(bag) => bag,
+            None => PUZZLE_BAG.parse()?,
+        };
+        Ok(Games { palette, bag, games })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Game {
     pub id: u16,
     reveals: Vec<Reveal>,
@@ -10,96 +62,316 @@ pub struct Game {
 impl Game {
     // A game line looks like this:
     // Game 2: 3 red, 1 blue, 2 green; 1 blue, 9 green; 1 red, 10 green
-    pub fn parse_game(line: &str) -> Result<Game, GameError> {
-        let id_parts = line.split(":").collect::<Vec<&str>>();
-        // get the id
-        let _id = (&(id_parts[0])[5..]).parse();
-
-        let id = match _id {
-            Ok(id) => id,
-            Err(_) => {
-                return Err(GameError {
-                    message: String::from("Game ID could not be parsed"),
-                })
-            }
-        };
+    //
+    //   game   = "Game" id ":" reveal { ";" reveal }
+    //   reveal = cube { "," cube }
+    //   cube   = count color
+    pub fn parse_game(line: &str) -> Result<Game, Error> {
+        Self::parse_with_palette(line, &mut Palette::standard())
+    }
+
+    /// Parses a game whose colors must belong to `palette`, or are added to it if it is not
+    /// declared.
+    pub fn parse_with_palette(line: &str, palette: &mut Palette) -> Result<Game, Error> {
+        let header = (lexeme(keyword("Game")), expect(integer::<u16>, "a game id"), lexeme(tag(":")));
+        let game = (header, separated1(cubes, (tag(";"), ws)));
+        let ((_, id, _), reveals) = parse_all(&game, line)?;
+        let reveals = reveals
+            .into_iter()
+            .map(|cubes| counts(cubes, palette, "revealed").map(|cubes| Reveal { cubes }))
+            .collect::<Result<_, _>>()?;
+        Ok(Game { id, reveals })
+    }
+
+    pub fn reveals(&self) -> &[Reveal] {
+        &self.reveals
+    }
+
+    /// Whether every reveal fits into `bag`.
+    pub fn is_valid(&self, bag: &Bag) -> bool {
+        self.reveals
+            .iter()
+            .all(|reveal| reveal.cubes().all(|(color, count)| count <= bag.count(color)))
+    }
 
-        //get reveals
-        let game_raw = id_parts[1];
-        let reveals_raw = game_raw.split(";").collect::<Vec<&str>>();
-        let mut reveals: Vec<Reveal> = vec![];
-        for reveal_raw in reveals_raw.iter() {
-            let cubes = reveal_raw.trim().split(", ").collect::<Vec<&str>>();
-            let mut color_counts: HashMap<CubeColor, u16> = HashMap::new();
-
-            for cube in cubes.iter() {
-                let cube_parts = cube.split(" ").collect::<Vec<&str>>();
-                let cube_color = CubeColor::from_str(cube_parts[1]).unwrap();
-                color_counts.insert(
-                    cube_color,
-                    cube_parts[0]
-                        .parse()
-                        .expect("color count could not be parsed"),
-                );
-
-                reveals.push(Reveal {
-                    red: *color_counts.get(&CubeColor::Red).unwrap_or(&0),
-                    green: *(color_counts.get(&CubeColor::Green)).unwrap_or(&0),
-                    blue: *color_counts.get(&CubeColor::Blue).unwrap_or(&0),
-                });
-            }
+    pub fn get_max_cube_per_color(&self, cube_color: &CubeColor) -> u16 {
+        self.reveals.iter().map(|reveal| reveal.count(cube_color)).max().unwrap_or(0)
+    }
+
+    /// The smallest bag the game is possible with.
+    pub fn minimal_bag(&self) -> Bag {
+        let mut cubes: BTreeMap<CubeColor, u16> = BTreeMap::new();
+        for (color, count) in self.reveals.iter().flat_map(Reveal::cubes) {
+            let max = cubes.entry(color.clone()).or_default();
+            *max = (*max).max(count);
         }
+        Bag::new(cubes)
+    }
+}
 
-        Ok(Game { id, reveals })
+/// The color of a cube as the input names it, e.g. `red`. Names are case insensitive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CubeColor(String);
+
+impl CubeColor {
+    pub fn new(name: &str) -> Self {
+        Self(name.to_ascii_lowercase())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CubeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
+}
+
+impl FromStr for CubeColor {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Error> {
+        parse_all(&color, name)
+    }
+}
 
-    pub fn is_valid(self: &Self, red: u16, green: u16, blue: u16) -> bool {
-        for reveal in &self.reveals {
-            if reveal.red > red || reveal.green > green || reveal.blue > blue {
-                return false;
-            }
+/// The colors the cubes come in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<CubeColor>,
+    declared: bool,
+}
+
+impl Palette {
+    /// Red, green and blue, extended by every other color the games show.
+    pub fn standard() -> Self {
+        Self {
+            colors: ["red", "green", "blue"].map(CubeColor::new).to_vec(),
+            declared: false,
         }
-        true
     }
 
-    pub fn get_max_cube_per_color(self: &Self, cube_color: CubeColor) -> u16 {
-        let mut max: u16 = 0;
-        for reveal in &self.reveals {
-            match cube_color {
-                CubeColor::Red => max = if reveal.red > max {reveal.red} else {max},
-                CubeColor::Green => max = if reveal.green > max {reveal.green} else {max},
-                CubeColor::Blue => max = if reveal.blue > max {reveal.blue} else {max},
-            }
+    /// Exactly `colors`; a game showing another color is an error.
+    pub fn declared(colors: Vec<CubeColor>) -> Self {
+        Self { colors, declared: true }
+    }
+
+    /// In the order they were declared or first shown.
+    pub fn colors(&self) -> &[CubeColor] {
+        &self.colors
+    }
+
+    pub fn is_declared(&self) -> bool {
+        self.declared
+    }
+
+    fn admit(&mut self, color: &CubeColor) -> Result<(), Error> {
+        if self.colors.contains(color) {
+            Ok(())
+        } else if self.declared {
+            let colors: Vec<&str> = self.colors.iter().map(CubeColor::name).collect();
+            Err(Error::parse(format!(
+                "expected one of the colors {}, found '{}'",
+                colors.join(", "),
+                color
+            )))
+        } else {
+            self.colors.push(color.clone());
+            Ok(())
         }
-        max
     }
+}
 
+/// The cubes shown at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reveal {
+    cubes: BTreeMap<CubeColor, u16>,
 }
 
-#[derive(Debug)]
-pub struct GameError {
-    pub message: String,
+impl Reveal {
+    pub fn count(&self, color: &CubeColor) -> u16 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    /// The colors shown and how many of each, by color name.
+    pub fn cubes(&self) -> impl Iterator<Item = (&CubeColor, u16)> {
+        self.cubes.iter().map(|(color, count)| (color, *count))
+    }
+}
+
+/// How many cubes of each color a bag holds; it holds none of the colors it does not name.
+/// Written like a reveal, e.g. `12 red, 13 green, 14 blue`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Bag {
+    cubes: BTreeMap<CubeColor, u16>,
+}
+
+impl Bag {
+    pub fn new(cubes: impl IntoIterator<Item = (CubeColor, u16)>) -> Self {
+        let cubes = cubes.into_iter().filter(|(_, count)| *count > 0).collect();
+        Self { cubes }
+    }
+
+    pub fn count(&self, color: &CubeColor) -> u16 {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    /// The colors the bag holds and how many of each, by color name.
+    pub fn cubes(&self) -> impl Iterator<Item = (&CubeColor, u16)> {
+        self.cubes.iter().map(|(color, count)| (color, *count))
+    }
+
+    pub fn total(&self) -> u32 {
+        self.cubes.values().map(|count| u32::from(*count)).sum()
+    }
 }
 
-impl Error for GameError {}
+impl FromStr for Bag {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let cubes = parse_all(&cubes, text)?;
+        Ok(Bag::new(counts(cubes, &mut Palette::standard(), "listed")?))
+    }
+}
 
-impl fmt::Display for GameError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "ERROR(Game Error): {}\n", self.message)
+impl fmt::Display for Bag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.cubes.is_empty() {
+            return f.write_str("no cubes");
+        }
+        let cubes: Vec<String> = self.cubes().map(|(color, count)| format!("{} {}", count, color)).collect();
+        f.write_str(&cubes.join(", "))
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, EnumString, Display)]
-#[strum(ascii_case_insensitive)]
-pub enum CubeColor {
-    Red,
-    Green,
-    Blue,
+/// Counted colors together with the columns the color was named at.
+type Cubes = Vec<(u16, (CubeColor, Range<usize>))>;
+
+fn color(cursor: Cursor<'_>) -> ParseResult<'_, CubeColor> {
+    map(expect(word, "a color"), CubeColor::new).parse(cursor)
 }
 
-#[derive(Debug)]
-struct Reveal {
-    red: u16,
-    green: u16,
-    blue: u16,
+fn cubes(cursor: Cursor<'_>) -> ParseResult<'_, Cubes> {
+    let cube = (lexeme(expect(integer::<u16>, "a cube count")), lexeme(spanned(color)));
+    separated1(cube, (tag(","), ws)).parse(cursor)
+}
+
+/// The count of every color, each of which must belong to `palette` and be named only once.
+fn counts(cubes: Cubes, palette: &mut Palette, verb: &str) -> Result<BTreeMap<CubeColor, u16>, Error> {
+    let mut counts = BTreeMap::new();
+    for (count, (color, columns)) in cubes {
+        palette.admit(&color).map_err(|e| e.with_columns(columns.clone()))?;
+        if counts.insert(color.clone(), count).is_some() {
+            return Err(Error::parse(format!("{} is {} more than once", color, verb)).with_columns(columns));
+        }
+    }
+    Ok(counts)
+}
+
+fn parse_colors(line: &str) -> Result<Vec<CubeColor>, Error> {
+    let header = (
+        lexeme(keyword("Colors")),
+        lexeme(tag(":")),
+        separated1(lexeme(spanned(color)), (tag(","), ws)),
+    );
+    let (_, _, colors) = parse_all(&header, line)?;
+    let mut declared: Vec<CubeColor> = Vec::new();
+    for (color, columns) in colors {
+        if declared.contains(&color) {
+            return Err(Error::parse(format!("{} is declared more than once", color)).with_columns(columns));
+        }
+        declared.push(color);
+    }
+    Ok(declared)
+}
+
+fn parse_bag(line: &str, palette: &mut Palette) -> Result<Bag, Error> {
+    let header = (lexeme(keyword("Bag")), lexeme(tag(":")), cubes);
+    let (_, _, cubes) = parse_all(&header, line)?;
+    Ok(Bag::new(counts(cubes, palette, "listed")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{parse_lines, ErrorKind};
+
+    fn counts(reveal: &Reveal) -> Vec<(&str, u16)> {
+        reveal.cubes().map(|(color, count)| (color.name(), count)).collect()
+    }
+
+    #[test]
+    fn test_parse_game() {
+        let game = Game::parse_game("Game 12: 3 blue, 4 Red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+        assert_eq!(game.id, 12);
+        let reveals: Vec<_> = game.reveals().iter().map(counts).collect();
+        assert_eq!(
+            reveals,
+            vec![
+                vec![("blue", 3), ("red", 4)],
+                vec![("blue", 6), ("green", 2), ("red", 1)],
+                vec![("green", 2)],
+            ]
+        );
+        assert_eq!(game.minimal_bag().to_string(), "6 blue, 2 green, 4 red");
+        assert!(game.is_valid(&PUZZLE_BAG.parse().unwrap()));
+        assert!(!game.is_valid(&"6 blue, 2 green, 3 red".parse().unwrap()));
+    }
+
+    /// Checks the message and the 1-based column of the error of a malformed line.
+    fn assert_error(line: &str, message: &str, column: usize) {
+        let error = Game::parse_game(line).unwrap_err();
+        assert_eq!(
+            (error.kind(), error.message(), error.column()),
+            (ErrorKind::Parse, message, Some(column)),
+            "{}",
+            line
+        );
+    }
+
+    #[test]
+    fn test_malformed_games() {
+        assert_error("Gam 1: 3 red", "expected 'Game', found 'Gam'", 1);
+        assert_error("Gamé 1: 3 red", "expected 'Game', found 'Gamé'", 1);
+        assert_error("Game 1: 3 rød", "expected end of line, found 'ø'", 12);
+        assert_error("Game x: 3 red", "expected a game id, found 'x'", 6);
+        assert_error("Game 1 3 red", "expected ':', found ' '", 7);
+        assert_error("Game 1: red", "expected a cube count, found 'r'", 9);
+        assert_error("Game 1: 3 5", "expected a color, found '5'", 11);
+        assert_error("Game 1: 3", "expected a color, found end of line", 10);
+        assert_error("Game 1: 3 red;", "expected a cube count, found end of line", 15);
+        assert_error("Game 1: 3 red 4 blue", "expected end of line, found '4'", 15);
+        assert_error("Game 1: 3 red, 1 blue, 4 red; 2 red", "red is revealed more than once", 26);
+        assert_eq!(Game::parse_game("Game 1: 70000 red").unwrap_err().kind(), ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_error_names_the_line() {
+        let error = parse_lines("Game 1: 3 red\nGame 2: 3 red, 3 red", Game::parse_game).unwrap_err();
+        assert_eq!((error.line(), error.column()), (Some(2), Some(18)));
+    }
+
+    #[test]
+    fn test_palette() {
+        let games = Games::parse("Game 1: 1 yellow, 2 red\nGame 2: 3 black").unwrap();
+        let colors: Vec<&str> = games.palette.colors().iter().map(CubeColor::name).collect();
+        assert_eq!(colors, vec!["red", "green", "blue", "yellow", "black"]);
+        assert_eq!(games.bag, PUZZLE_BAG.parse().unwrap());
+
+        let games = Games::parse("Colors: yellow, black\nBag: 2 yellow\nGame 1: 1 yellow").unwrap();
+        assert_eq!(games.palette.colors(), &[CubeColor::new("yellow"), CubeColor::new("black")]);
+        assert_eq!(games.bag.to_string(), "2 yellow");
+
+        let error = Games::parse("Colors: yellow\nGame 1: 1 yellow, 2 red").unwrap_err();
+        assert_eq!((error.line(), error.column()), (Some(2), Some(21)));
+        assert_eq!(error.message(), "expected one of the colors yellow, found 'red'");
+        let error = Games::parse("Game 1: 1 red\nColors: red").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        let error = Games::parse("Colors: red, Red").unwrap_err();
+        assert_eq!(error.message(), "red is declared more than once");
+        assert_eq!("1 red, 2 red".parse::<Bag>().unwrap_err().message(), "red is listed more than once");
+    }
 }
@@ -0,0 +1,140 @@
+use common::{Anonymize, Generate};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use crate::{
+    game::{Bag, CubeColor, Palette, PUZZLE_BAG},
+    Day02,
+};
+
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+/// `size` games with ids 1..=size, each with one to six reveals of up to 20 cubes per color.
+impl Generate for Day02 {
+    /// Game ids are `u16`.
+    const MAX_SIZE: usize = u16::MAX as usize;
+
+    fn generate(rng: &mut StdRng, size: usize) -> String {
+        (1..=size)
+            .map(|id| {
+                let reveals: Vec<String> = (0..rng.gen_range(1..=6)).map(|_| reveal(rng)).collect();
+                format!("Game {}: {}\n", id, reveals.join("; "))
+            })
+            .collect()
+    }
+}
+
+/// The smallest bag of every game, and the palette and bag of part one of the input.
+#[derive(Debug)]
+pub struct Profile {
+    bags: Vec<Bag>,
+    palette: Palette,
+    bag: Bag,
+}
+
+/// As many games as the real input, each with the smallest bag of a random real game, after
+/// the same headers. Both answers only depend on those, so they are of the same magnitude as the
+/// real ones.
+impl Anonymize for Day02 {
+    type Profile = Profile;
+
+    fn profile(input: &Self::Input) -> Profile {
+        Profile {
+            bags: input.games.iter().map(|game| game.minimal_bag()).collect(),
+            palette: input.palette.clone(),
+            bag: input.bag.clone(),
+        }
+    }
+
+    fn synthesize(rng: &mut StdRng, profile: &Profile) -> String {
+        let mut headers = String::new();
+        if profile.palette.is_declared() {
+            let colors: Vec<&str> = profile.palette.colors().iter().map(CubeColor::name).collect();
+            headers.push_str(&format!("Colors: {}\n", colors.join(", ")));
+        }
+        if profile.bag.total() > 0 && PUZZLE_BAG.parse::<Bag>().ok().as_ref() != Some(&profile.bag) {
+            headers.push_str(&format!("Bag: {}\n", profile.bag));
+        }
+        let games = (1..=profile.bags.len()).map(|id| {
+            let mut bag = profile.bags.choose(rng).cloned().unwrap_or_default();
+            if bag.total() == 0 {
+                bag = Bag::new(profile.palette.colors().first().map(|color| (color.clone(), 1)));
+            }
+            let mut reveals: Vec<Vec<String>> = vec![Vec::new(); rng.gen_range(1..=6)];
+            for (color, max) in bag.cubes() {
+                // One reveal shows the maximum, some of the others show fewer cubes
+                let peak = rng.gen_range(0..reveals.len());
+                for (i, reveal) in reveals.iter_mut().enumerate() {
+                    if i == peak {
+                        reveal.push(format!("{} {}", max, color));
+                    } else if rng.gen_bool(0.5) {
+                        reveal.push(format!("{} {}", rng.gen_range(1..=max), color));
+                    }
+                }
+            }
+            reveals.retain(|reveal| !reveal.is_empty());
+            let reveals: Vec<String> = reveals
+                .iter_mut()
+                .map(|reveal| {
+                    reveal.shuffle(rng);
+                    reveal.join(", ")
+                })
+                .collect();
+            format!("Game {}: {}\n", id, reveals.join("; "))
+        });
+        headers + &games.collect::<String>()
+    }
+}
+
+/// Each color at most once, in random order.
+fn reveal(rng: &mut StdRng) -> String {
+    let mut colors = COLORS.to_vec();
+    colors.shuffle(rng);
+    colors.truncate(rng.gen_range(1..=COLORS.len()));
+    let cubes: Vec<String> = colors.iter().map(|color| format!("{} {}", rng.gen_range(1..=20), color)).collect();
+    cubes.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{generate, samples, Solution};
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_game_ids_fit_in_u16() {
+        assert!(generate::<Day02>(1, u16::MAX as usize + 1).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_game_fits_its_maxima(sample in samples::<Day02>(1..50)) {
+            let games = Day02::parse(&sample.input).unwrap();
+            prop_assert_eq!(games.games.len(), sample.size);
+            for game in &games.games {
+                let bag = game.minimal_bag();
+                prop_assert!(game.is_valid(&bag));
+                let first = bag.cubes().next().map(|(color, count)| (color.clone(), count));
+                if let Some((color, count)) = first {
+                    let smaller = Bag::new(bag.cubes().map(|(other, n)| (other.clone(), if *other == color { count - 1 } else { n })));
+                    prop_assert!(!game.is_valid(&smaller));
+                }
+            }
+        }
+
+        #[test]
+        fn prop_part_one_is_at_most_the_sum_of_ids(sample in samples::<Day02>(1..50)) {
+            let games = Day02::parse(&sample.input).unwrap();
+            let sum: u32 = Day02::part_one(&games).unwrap().to_string().parse().unwrap();
+            prop_assert!(sum as usize <= sample.size * (sample.size + 1) / 2);
+        }
+
+        #[test]
+        fn prop_anonymized_games_have_real_maxima(sample in samples::<Day02>(1..50)) {
+            let real = Day02::profile(&Day02::parse(&sample.input).unwrap());
+            let synthetic = Day02::profile(&Day02::parse(&sample.anonymize::<Day02>().unwrap()).unwrap());
+            prop_assert_eq!(synthetic.bags.len(), sample.size);
+            prop_assert!(synthetic.bags.iter().all(|bag| real.bags.contains(bag)));
+        }
+    }
+}
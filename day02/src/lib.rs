@@ -0,0 +1,97 @@
+use std::fmt::Display;
+
+use common::{Error, Solution};
+
+use crate::game::{Games, Palette};
+pub mod estimate;
+mod generate;
+pub mod game;
+pub mod query;
+pub mod solver;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Games;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Games::parse(data)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<impl Display, Error> {
+        Ok(input
+            .games
+            .iter()
+            .filter(|game| game.is_valid(&input.bag))
+            .map(|game| u32::from(game.id))
+            .sum::<u32>())
+    }
+
+    /// The power of a game is the product of its maxima over the declared palette, or else over
+    /// red, green, blue and whichever other colors the game itself shows. A color only another
+    /// game shows does not make it zero.
+    fn part_two(input: &Self::Input) -> Result<impl Display, Error> {
+        let standard = Palette::standard();
+        let colors = if input.palette.is_declared() {
+            input.palette.colors()
+        } else {
+            standard.colors()
+        };
+        Ok(input
+            .games
+            .iter()
+            .map(|game| {
+                let bag = game.minimal_bag();
+                let others = bag.cubes().filter(|(color, _)| !colors.contains(color)).map(|(_, count)| count);
+                colors
+                    .iter()
+                    .map(|color| bag.count(color))
+                    .chain(others)
+                    .map(u64::from)
+                    .product::<u64>()
+            })
+            .sum::<u64>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{solve, Part};
+
+    #[test]
+    fn test_solve_example() {
+        let answers = solve::<Day02>(EXAMPLE_GAMES, &Part::ALL).unwrap();
+        let answers: Vec<(Part, &str)> = answers.iter().map(|answer| (answer.part, answer.value.as_str())).collect();
+        assert_eq!(answers, vec![(Part::One, "8"), (Part::Two, "2286")]);
+    }
+
+    #[test]
+    fn test_solve_variant() {
+        let answers = solve::<Day02>(VARIANT_GAMES, &Part::ALL).unwrap();
+        let answers: Vec<(Part, &str)> = answers.iter().map(|answer| (answer.part, answer.value.as_str())).collect();
+        assert_eq!(answers, vec![(Part::One, "1"), (Part::Two, "84")]);
+    }
+
+    #[test]
+    fn test_solve_with_an_undeclared_color() {
+        let answers = solve::<Day02>(EXTRA_COLOR_GAMES, &Part::ALL).unwrap();
+        let answers: Vec<(Part, &str)> = answers.iter().map(|answer| (answer.part, answer.value.as_str())).collect();
+        // Game 1 has power 4 * 2 * 3 and game 2, which alone shows yellow, 1 * 2 * 1 * 2
+        assert_eq!(answers, vec![(Part::One, "1"), (Part::Two, "28")]);
+    }
+
+    const EXAMPLE_GAMES: &str = r"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    const VARIANT_GAMES: &str = r"Colors: red, green, blue, yellow, black
+Bag: 12 red, 13 green, 14 blue, 2 yellow, 1 black
+Game 1: 3 blue, 4 red, 1 yellow; 1 red, 2 green, 6 blue, 1 black
+Game 2: 1 blue, 2 green, 3 yellow; 3 green, 4 blue, 1 red, 1 black";
+
+    const EXTRA_COLOR_GAMES: &str = r"Game 1: 3 blue, 4 red; 2 green
+Game 2: 1 blue, 2 green, 1 red; 2 yellow";
+}
@@ -0,0 +1,594 @@
+//! A small query language over the games, e.g.
+//!
+//! ```text
+//! where max(red) > 10 and min(blue) >= 2 select sum(id)
+//! ```
+//!
+//! `where` keeps the games a condition holds for, and `select` either lists values of every game
+//! kept or aggregates them into a single row. The values of a game are its `id`, the number of
+//! `reveals` and aggregates over the reveals of one color or of all `cubes` shown: `max`, `min`,
+//! `avg`, `sum`, and `count`, which counts the reveals showing any. Values combine with
+//! `+ - * /`, comparisons with `and`, `or`, `not` and parentheses. The games are aggregated with
+//! the same `sum`, `min`, `max`, `avg` and `count` of a value, and counted with `count(*)`. The
+//! puzzle's own questions read:
+//!
+//! ```text
+//! where max(red) <= 12 and max(green) <= 13 and max(blue) <= 14 select sum(id)
+//! select sum(max(red) * max(green) * max(blue))
+//! ```
+
+use std::{fmt, ops::Range};
+
+use common::{
+    parser::{
+        expect, integer, keyword, lexeme, map, opt, or, parse_all, preceded, separated1, spanned, tag, word, ws, Cursor, ParseResult,
+        Parser,
+    },
+    Error,
+};
+
+use crate::game::{CubeColor, Game, Games, Reveal};
+
+/// A parsed query, see the module documentation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    filter: Option<Condition>,
+    /// The text of every selection, to head its column.
+    columns: Vec<String>,
+    select: Select,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Select {
+    /// A row per game.
+    Values(Vec<Expr>),
+    /// A single row.
+    Aggregates(Vec<Aggregation>),
+}
+
+/// One selection of a query: a value of every game, or an aggregation over the games.
+#[derive(Debug, Clone, PartialEq)]
+enum Selection {
+    Value(Expr),
+    Aggregate(Aggregation),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Aggregation {
+    Games,
+    Of(Aggregate, Expr),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    Min,
+    Max,
+    Avg,
+    Sum,
+    Count,
+}
+
+/// A value of one game.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Id,
+    Reveals,
+    Reveal(Aggregate, Quantity),
+    Binary(Box<Expr>, Operator, Box<Expr>),
+}
+
+/// A value of one reveal.
+#[derive(Debug, Clone, PartialEq)]
+enum Quantity {
+    Cubes,
+    /// With the columns it is named at.
+    Color(CubeColor, Range<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Compare(Expr, Comparison, Expr),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Query {
+    //   query      = [ "where" condition ] "select" selection { "," selection }
+    //   selection  = "count" "(" "*" ")" | aggregate "(" expr ")" | expr
+    //   condition  = conjunction { "or" conjunction }
+    //   conjunction= negation { "and" negation }
+    //   negation   = "not" negation | "(" condition ")" | expr comparison expr
+    //   expr       = term { ( "+" | "-" ) term }
+    //   term       = factor { ( "*" | "/" ) factor }
+    //   factor     = number | "id" | "reveals" | aggregate "(" ( "cubes" | color ) ")" | "(" expr ")"
+    pub fn parse(text: &str) -> Result<Query, Error> {
+        let select = preceded(lexeme(keyword("select")), separated1(spanned(selection), lexeme(tag(","))));
+        let (filter, selections) = parse_all(&preceded(ws, (filter, select)), text)?;
+        let columns = selections
+            .iter()
+            .map(|(_, columns)| {
+                let name: String = text.chars().skip(columns.start).take(columns.len()).collect();
+                name.trim_end().to_string()
+            })
+            .collect();
+
+        let (mut values, mut aggregations) = (Vec::new(), Vec::new());
+        for (selection, _) in selections {
+            match selection {
+                Selection::Value(value) => values.push(value),
+                Selection::Aggregate(aggregation) => aggregations.push(aggregation),
+            }
+        }
+        let select = match (values.is_empty(), aggregations.is_empty()) {
+            (false, true) => Select::Values(values),
+            (true, false) => Select::Aggregates(aggregations),
+            _ => {
+                return Err(Error::validation(
+                    "A query selects either values of every game or aggregates over the games, not both",
+                ))
+            }
+        };
+        Ok(Query { filter, columns, select })
+    }
+
+    /// Evaluates the query over `games`. Every color it names must be one of their palette.
+    pub fn run(&self, games: &Games) -> Result<Table, Error> {
+        let mut unknown = None;
+        let mut check = |color: &CubeColor, columns: &Range<usize>| {
+            if unknown.is_none() && !games.palette.colors().contains(color) {
+                unknown = Some((color.clone(), columns.clone()));
+            }
+        };
+        if let Some(filter) = &self.filter {
+            filter.colors(&mut check);
+        }
+        match &self.select {
+            Select::Values(values) => values.iter().for_each(|value| value.colors(&mut check)),
+            Select::Aggregates(aggregations) => aggregations.iter().for_each(|aggregation| {
+                if let Aggregation::Of(_, value) = aggregation {
+                    value.colors(&mut check);
+                }
+            }),
+        }
+        if let Some((color, columns)) = unknown {
+            let colors: Vec<&str> = games.palette.colors().iter().map(CubeColor::name).collect();
+            return Err(Error::validation(format!("The games show no {} cubes, only {}", color, colors.join(", "))).with_columns(columns));
+        }
+
+        let kept: Vec<&Game> = games
+            .games
+            .iter()
+            .filter(|game| self.filter.as_ref().is_none_or(|filter| filter.holds(game)))
+            .collect();
+        let rows = match &self.select {
+            Select::Values(values) => kept
+                .iter()
+                .map(|game| values.iter().map(|value| Some(value.of(game))).collect())
+                .collect(),
+            Select::Aggregates(aggregations) => {
+                let row = aggregations
+                    .iter()
+                    .map(|aggregation| match aggregation {
+                        Aggregation::Games => Some(kept.len() as f64),
+                        Aggregation::Of(aggregate, value) => aggregate.apply(kept.iter().map(|game| value.of(game))),
+                    })
+                    .collect();
+                vec![row]
+            }
+        };
+        Ok(Table {
+            columns: self.columns.clone(),
+            rows,
+        })
+    }
+}
+
+/// Parses `query` and runs it over the games of `data`. Errors in the query name it as the file
+/// `query`.
+pub fn run(data: &str, query: &str) -> Result<String, Error> {
+    let games = Games::parse(data)?;
+    let table = Query::parse(query)
+        .and_then(|query| query.run(&games))
+        .map_err(|e| e.with_file("query").with_line(1, query))?;
+    Ok(table.to_string())
+}
+
+/// The result of a query: a row per game kept, or a single row of aggregates. Aggregates of no
+/// values have none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Option<f64>>>,
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                // Averages are shown with three decimals at most
+                row.iter()
+                    .map(|value| value.map_or("-".to_string(), |value| ((value * 1000.0).round() / 1000.0).to_string()))
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| rows.iter().map(|row| row[i].len()).chain([column.len()]).max().unwrap_or(0))
+            .collect();
+        for row in [&self.columns].into_iter().chain(&rows) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+                .collect();
+            writeln!(f, "{}", cells.join("  "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Aggregate {
+    /// Sum and count of no values are zero, the others are none.
+    fn apply(self, values: impl Iterator<Item = f64>) -> Option<f64> {
+        let (mut len, mut nonzero, mut sum) = (0, 0, 0.0);
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for value in values {
+            len += 1;
+            nonzero += usize::from(value != 0.0);
+            sum += value;
+            min = min.min(value);
+            max = max.max(value);
+        }
+        match self {
+            Aggregate::Sum => Some(sum),
+            Aggregate::Count => Some(nonzero as f64),
+            _ if len == 0 => None,
+            Aggregate::Min => Some(min),
+            Aggregate::Max => Some(max),
+            Aggregate::Avg => Some(sum / len as f64),
+        }
+    }
+}
+
+impl Quantity {
+    fn of(&self, reveal: &Reveal) -> f64 {
+        match self {
+            Quantity::Cubes => reveal.cubes().map(|(_, count)| f64::from(count)).sum(),
+            Quantity::Color(color, _) => f64::from(reveal.count(color)),
+        }
+    }
+}
+
+impl Expr {
+    fn of(&self, game: &Game) -> f64 {
+        match self {
+            Expr::Number(number) => *number,
+            Expr::Id => f64::from(game.id),
+            Expr::Reveals => game.reveals().len() as f64,
+            Expr::Reveal(Aggregate::Max, Quantity::Color(color, _)) => f64::from(game.get_max_cube_per_color(color)),
+            Expr::Reveal(aggregate, quantity) => aggregate
+                .apply(game.reveals().iter().map(|reveal| quantity.of(reveal)))
+                .unwrap_or(0.0),
+            Expr::Binary(left, operator, right) => {
+                let (left, right) = (left.of(game), right.of(game));
+                match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                }
+            }
+        }
+    }
+
+    fn colors(&self, f: &mut impl FnMut(&CubeColor, &Range<usize>)) {
+        match self {
+            Expr::Reveal(_, Quantity::Color(color, columns)) => f(color, columns),
+            Expr::Binary(left, _, right) => {
+                left.colors(f);
+                right.colors(f);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Condition {
+    fn holds(&self, game: &Game) -> bool {
+        match self {
+            Condition::Compare(left, comparison, right) => {
+                let (left, right) = (left.of(game), right.of(game));
+                match comparison {
+                    Comparison::Less => left < right,
+                    Comparison::LessOrEqual => left <= right,
+                    Comparison::Greater => left > right,
+                    Comparison::GreaterOrEqual => left >= right,
+                    Comparison::Equal => left == right,
+                    Comparison::NotEqual => left != right,
+                }
+            }
+            Condition::Not(condition) => !condition.holds(game),
+            Condition::And(left, right) => left.holds(game) && right.holds(game),
+            Condition::Or(left, right) => left.holds(game) || right.holds(game),
+        }
+    }
+
+    fn colors(&self, f: &mut impl FnMut(&CubeColor, &Range<usize>)) {
+        match self {
+            Condition::Compare(left, _, right) => {
+                left.colors(f);
+                right.colors(f);
+            }
+            Condition::Not(condition) => condition.colors(f),
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                left.colors(f);
+                right.colors(f);
+            }
+        }
+    }
+}
+
+const MISPLACED_AGGREGATE: &str = "Aggregates over the games are only allowed as selections, not in where or inside a value";
+
+/// `parser` as a token standing for `value`.
+fn token<'a, T: Clone>(parser: impl Parser<'a, &'a str>, value: T) -> impl Fn(Cursor<'a>) -> ParseResult<'a, T> {
+    lexeme(map(parser, move |_| value.clone()))
+}
+
+fn parenthesized<'a, T>(parser: impl Parser<'a, T>) -> impl Fn(Cursor<'a>) -> ParseResult<'a, T> {
+    map((lexeme(tag("(")), parser, lexeme(tag(")"))), |(_, value, _)| value)
+}
+
+/// `operand`s joined left to right by `operator`; once an operator is seen the next operand is
+/// mandatory.
+fn chain<'a, T, O>(
+    operand: fn(Cursor<'a>) -> ParseResult<'a, T>,
+    operator: impl Parser<'a, O>,
+    combine: impl Fn(T, O, T) -> T,
+    cursor: Cursor<'a>,
+) -> ParseResult<'a, T> {
+    let (mut left, mut cursor) = operand(cursor)?;
+    while let Ok((operator, next)) = operator.parse(cursor) {
+        let (right, next) = operand(next)?;
+        left = combine(left, operator, right);
+        cursor = next;
+    }
+    Ok((left, cursor))
+}
+
+fn filter(cursor: Cursor<'_>) -> ParseResult<'_, Option<Condition>> {
+    match lexeme(keyword("where")).parse(cursor) {
+        Ok((_, cursor)) => map(condition, Some).parse(cursor),
+        Err(_) => Ok((None, cursor)),
+    }
+}
+
+fn selection(cursor: Cursor<'_>) -> ParseResult<'_, Selection> {
+    let count = map((lexeme(keyword("count")), parenthesized(lexeme(tag("*")))), |_| {
+        Selection::Aggregate(Aggregation::Games)
+    });
+    let games = map((aggregate, parenthesized(expr)), |(aggregate, value)| {
+        Selection::Aggregate(Aggregation::Of(aggregate, value))
+    });
+    or(or(count, games), map(expr, Selection::Value)).parse(cursor)
+}
+
+fn aggregate(cursor: Cursor<'_>) -> ParseResult<'_, Aggregate> {
+    let aggregates = or(
+        or(
+            or(token(keyword("min"), Aggregate::Min), token(keyword("max"), Aggregate::Max)),
+            or(token(keyword("avg"), Aggregate::Avg), token(keyword("sum"), Aggregate::Sum)),
+        ),
+        token(keyword("count"), Aggregate::Count),
+    );
+    expect(aggregates, "an aggregate").parse(cursor)
+}
+
+fn condition(cursor: Cursor<'_>) -> ParseResult<'_, Condition> {
+    let or_keyword = token(keyword("or"), ());
+    chain(
+        conjunction,
+        or_keyword,
+        |left, (), right| Condition::Or(Box::new(left), Box::new(right)),
+        cursor,
+    )
+}
+
+fn conjunction(cursor: Cursor<'_>) -> ParseResult<'_, Condition> {
+    let and_keyword = token(keyword("and"), ());
+    chain(
+        negation,
+        and_keyword,
+        |left, (), right| Condition::And(Box::new(left), Box::new(right)),
+        cursor,
+    )
+}
+
+fn negation(cursor: Cursor<'_>) -> ParseResult<'_, Condition> {
+    let not = map(preceded(lexeme(keyword("not")), negation), |condition| {
+        Condition::Not(Box::new(condition))
+    });
+    let comparison = map((expr, comparison, expr), |(left, comparison, right)| {
+        Condition::Compare(left, comparison, right)
+    });
+    or(or(not, parenthesized(condition)), comparison).parse(cursor)
+}
+
+fn comparison(cursor: Cursor<'_>) -> ParseResult<'_, Comparison> {
+    let comparisons = or(
+        or(
+            or(
+                token(tag("<="), Comparison::LessOrEqual),
+                token(tag(">="), Comparison::GreaterOrEqual),
+            ),
+            or(token(tag("!="), Comparison::NotEqual), token(tag("="), Comparison::Equal)),
+        ),
+        or(token(tag("<"), Comparison::Less), token(tag(">"), Comparison::Greater)),
+    );
+    expect(comparisons, "a comparison").parse(cursor)
+}
+
+fn expr(cursor: Cursor<'_>) -> ParseResult<'_, Expr> {
+    let operator = or(token(tag("+"), Operator::Add), token(tag("-"), Operator::Subtract));
+    chain(
+        term,
+        operator,
+        |left, operator, right| Expr::Binary(Box::new(left), operator, Box::new(right)),
+        cursor,
+    )
+}
+
+fn term(cursor: Cursor<'_>) -> ParseResult<'_, Expr> {
+    let operator = or(token(tag("*"), Operator::Multiply), token(tag("/"), Operator::Divide));
+    chain(
+        factor,
+        operator,
+        |left, operator, right| Expr::Binary(Box::new(left), operator, Box::new(right)),
+        cursor,
+    )
+}
+
+fn factor(cursor: Cursor<'_>) -> ParseResult<'_, Expr> {
+    // An aggregate of a value such as `sum(id)` would otherwise be taken for one of a color named
+    // `id`, and only fail once the games show no such cubes
+    match (aggregate, parenthesized(expr)).parse(cursor) {
+        Ok((_, end)) => return Err(Error::parse(MISPLACED_AGGREGATE).with_columns(cursor.columns_to(&end))),
+        Err(e) if e.message() == MISPLACED_AGGREGATE => return Err(e),
+        Err(_) => {}
+    }
+    let quantity = or(
+        token(keyword("cubes"), Quantity::Cubes),
+        lexeme(map(spanned(word), |(name, columns)| Quantity::Color(CubeColor::new(name), columns))),
+    );
+    let reveal = map((aggregate, parenthesized(quantity)), |(aggregate, quantity)| {
+        Expr::Reveal(aggregate, quantity)
+    });
+    let factors = or(
+        or(
+            or(map(number, Expr::Number), token(keyword("id"), Expr::Id)),
+            or(token(keyword("reveals"), Expr::Reveals), reveal),
+        ),
+        parenthesized(expr),
+    );
+    expect(factors, "a value").parse(cursor)
+}
+
+/// A decimal number such as `12` or `2.5`.
+fn number(cursor: Cursor<'_>) -> ParseResult<'_, f64> {
+    let fraction = preceded(tag("."), spanned(integer::<u64>));
+    let number = map((integer::<u64>, opt(fraction)), |(whole, fraction)| {
+        let fraction = fraction.map_or(0.0, |(digits, columns)| digits as f64 / 10_f64.powi(columns.len() as i32));
+        whole as f64 + fraction
+    });
+    lexeme(number).parse(cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_GAMES: &str = r"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    fn rows(query: &str) -> Vec<Vec<Option<f64>>> {
+        let games = Games::parse(EXAMPLE_GAMES).unwrap();
+        Query::parse(query).unwrap().run(&games).unwrap().rows
+    }
+
+    fn row(values: &[f64]) -> Vec<Vec<Option<f64>>> {
+        vec![values.iter().copied().map(Some).collect()]
+    }
+
+    #[test]
+    fn test_puzzle_questions() {
+        assert_eq!(
+            rows("where max(red) <= 12 and max(green) <= 13 and max(blue) <= 14 select sum(id)"),
+            row(&[8.0])
+        );
+        assert_eq!(rows("select sum(max(red) * max(green) * max(blue))"), row(&[2286.0]));
+    }
+
+    #[test]
+    fn test_aggregates() {
+        assert_eq!(rows("where max(red) > 10 and min(blue) >= 2 select sum(id)"), row(&[0.0]));
+        assert_eq!(
+            rows("where max(red) > 10 or min(blue) >= 1 select count(*), min(id), max(id)"),
+            row(&[4.0, 2.0, 5.0])
+        );
+        assert_eq!(
+            rows("where not (reveals = 3) select id, count(red), sum(cubes), avg(green)"),
+            vec![vec![Some(5.0), Some(2.0), Some(15.0), Some(2.5)]]
+        );
+        assert_eq!(rows("where (max(red) + 1) / 2 > 10 select sum(id), avg(id)"), row(&[3.0, 3.0]));
+        assert_eq!(
+            rows("where id > 5 select count(*), sum(id), max(id)"),
+            vec![vec![Some(0.0), Some(0.0), None]]
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let games = Games::parse(EXAMPLE_GAMES).unwrap();
+        let table = Query::parse("where id <= 2 select id, avg(blue) ").unwrap().run(&games).unwrap();
+        assert_eq!(table.to_string(), "id  avg(blue)\n 1          3\n 2          2\n");
+        let table = Query::parse("select avg(avg(cubes))").unwrap().run(&games).unwrap();
+        assert_eq!(table.to_string(), "avg(avg(cubes))\n           11.1\n");
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = run(EXAMPLE_GAMES, "where max(red) > select sum(id)").unwrap_err();
+        assert_eq!((error.file(), error.column()), (Some("query"), Some(18)));
+        assert_eq!(error.message(), "expected a value, found 's'");
+        let error = run(EXAMPLE_GAMES, "select sum(max(rde))").unwrap_err();
+        assert_eq!(error.message(), "The games show no rde cubes, only red, green, blue");
+        assert_eq!(error.column(), Some(16));
+        let error = run(EXAMPLE_GAMES, "select id, sum(id)").unwrap_err();
+        assert!(error.message().contains("not both"));
+        let error = run(EXAMPLE_GAMES, "select id +").unwrap_err();
+        assert_eq!(error.column(), Some(12));
+        let error = run(EXAMPLE_GAMES, "where sum(id) > 3 select id").unwrap_err();
+        assert_eq!((error.message(), error.column()), (MISPLACED_AGGREGATE, Some(7)));
+        let error = run(EXAMPLE_GAMES, "select max(sum(id))").unwrap_err();
+        assert_eq!((error.message(), error.column()), (MISPLACED_AGGREGATE, Some(12)));
+
+        // Queries are typed by hand, so any text has to come back as an error
+        for query in [
+            "select coün",
+            "sélect id",
+            "where ïd > 1 select id",
+            "select max(rød)",
+            "select id, ☃",
+        ] {
+            let error = run(EXAMPLE_GAMES, query).unwrap_err();
+            assert_eq!((error.file(), error.kind()), (Some("query"), common::ErrorKind::Parse), "{}", query);
+        }
+    }
+}
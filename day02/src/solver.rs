@@ -0,0 +1,168 @@
+//! Which bags make which games possible.
+//!
+//! A game is possible with a bag when the bag holds at least its smallest bag, so every question
+//! about a set of games comes down to comparing smallest bags color by color.
+
+use common::Error;
+
+use crate::game::{Bag, CubeColor, Game, Games};
+
+/// The smallest bag of every game, as counts in palette order, ready to be queried.
+#[derive(Debug)]
+pub struct BagSolver<'a> {
+    colors: &'a [CubeColor],
+    minima: Vec<(&'a Game, Vec<u16>)>,
+}
+
+/// The bags with which exactly a chosen set of games is possible: those holding at least `min`
+/// and at most one of the bags in `max`. A color no other game limits is held `u16::MAX` times
+/// in `max`. Without bags that rule out the other games, `max` is empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frontier {
+    pub min: Bag,
+    pub max: Vec<Bag>,
+}
+
+impl<'a> BagSolver<'a> {
+    pub fn new(input: &'a Games) -> Self {
+        let colors = input.palette.colors();
+        let minima = input
+            .games
+            .iter()
+            .map(|game| {
+                let bag = game.minimal_bag();
+                (game, colors.iter().map(|color| bag.count(color)).collect())
+            })
+            .collect();
+        Self { colors, minima }
+    }
+
+    fn bag(&self, counts: &[u16]) -> Bag {
+        Bag::new(self.colors.iter().cloned().zip(counts.iter().copied()))
+    }
+
+    /// The most cubes of each color any of `minima` holds.
+    fn largest<'b>(&self, minima: impl IntoIterator<Item = &'b Vec<u16>>) -> Vec<u16> {
+        minima.into_iter().fold(vec![0; self.colors.len()], |mut largest, counts| {
+            for (largest, count) in largest.iter_mut().zip(counts) {
+                *largest = (*largest).max(*count);
+            }
+            largest
+        })
+    }
+
+    /// The smallest bag every game is possible with.
+    pub fn minimal_bag(&self) -> Bag {
+        self.bag(&self.largest(self.minima.iter().map(|(_, counts)| counts)))
+    }
+
+    /// The games possible with some bag of at most `total` cubes, i.e. those whose smallest bag
+    /// is no larger.
+    pub fn possible_within(&self, total: u32) -> Vec<&'a Game> {
+        self.minima
+            .iter()
+            .filter(|(_, counts)| counts.iter().map(|count| u32::from(*count)).sum::<u32>() <= total)
+            .map(|(game, _)| *game)
+            .collect()
+    }
+
+    /// The bags with which the games of `ids`, and no others, are possible.
+    ///
+    /// Each other game has to be ruled out by holding fewer cubes of some color than it shows,
+    /// so the largest bags are found by capping one color per game, keeping only the bags no
+    /// other bag holds at least as many cubes of every color as.
+    pub fn frontier(&self, ids: &[u16]) -> Result<Frontier, Error> {
+        if let Some(id) = ids.iter().find(|id| !self.minima.iter().any(|(game, _)| game.id == **id)) {
+            return Err(Error::validation(format!("There is no game {}", id)));
+        }
+        let (admitted, ruled_out): (Vec<_>, Vec<_>) = self.minima.iter().partition(|(game, _)| ids.contains(&game.id));
+        let min = self.largest(admitted.iter().map(|(_, counts)| counts));
+
+        let mut max = vec![vec![u16::MAX; self.colors.len()]];
+        for (_, counts) in ruled_out {
+            let mut capped = Vec::new();
+            for bag in max {
+                if bag.iter().zip(counts).any(|(held, shown)| held < shown) {
+                    capped.push(bag);
+                    continue;
+                }
+                for (color, shown) in counts.iter().enumerate().filter(|(color, shown)| **shown > min[*color]) {
+                    let mut smaller = bag.clone();
+                    smaller[color] = shown - 1;
+                    capped.push(smaller);
+                }
+            }
+            max = maximal(capped);
+        }
+        max.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(Frontier {
+            min: self.bag(&min),
+            max: max.iter().map(|counts| self.bag(counts)).collect(),
+        })
+    }
+}
+
+/// The bags not dominated by another one, without duplicates.
+fn maximal(mut bags: Vec<Vec<u16>>) -> Vec<Vec<u16>> {
+    bags.sort_unstable();
+    bags.dedup();
+    let dominated = |bag: &Vec<u16>| {
+        bags.iter()
+            .any(|other| other != bag && other.iter().zip(bag).all(|(other, count)| other >= count))
+    };
+    let keep: Vec<bool> = bags.iter().map(|bag| !dominated(bag)).collect();
+    bags.into_iter().zip(keep).filter_map(|(bag, keep)| keep.then_some(bag)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_GAMES: &str = r"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    fn bag(text: &str) -> Bag {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_minimal_bag_and_totals() {
+        let games = Games::parse(EXAMPLE_GAMES).unwrap();
+        let solver = BagSolver::new(&games);
+        assert_eq!(solver.minimal_bag(), bag("20 red, 13 green, 15 blue"));
+        let ids = |total| solver.possible_within(total).iter().map(|game| game.id).collect::<Vec<_>>();
+        assert!(ids(7).is_empty());
+        assert_eq!(ids(12), vec![1, 2, 5]);
+        assert_eq!(ids(39), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_frontier() {
+        let games = Games::parse(EXAMPLE_GAMES).unwrap();
+        let solver = BagSolver::new(&games);
+        let frontier = solver.frontier(&[1, 2, 5]).unwrap();
+        assert_eq!(frontier.min, bag("6 red, 3 green, 6 blue"));
+        assert_eq!(
+            frontier.max,
+            vec![
+                bag("65535 red, 12 green, 14 blue"),
+                bag("19 red, 65535 green, 14 blue"),
+                bag("13 red, 65535 green, 65535 blue"),
+            ]
+        );
+        // The puzzle's bag lies between the smallest bag and one of the largest
+        let puzzle = bag(crate::game::PUZZLE_BAG);
+        assert!(frontier
+            .max
+            .iter()
+            .any(|max| puzzle.cubes().all(|(color, count)| count <= max.count(color))));
+
+        // Game 1 is possible whenever game 3 is
+        assert!(solver.frontier(&[3]).unwrap().max.is_empty());
+        assert_eq!(solver.frontier(&[1, 2, 3, 4, 5]).unwrap().max.len(), 1);
+        assert_eq!(solver.frontier(&[6]).unwrap_err().message(), "There is no game 6");
+    }
+}
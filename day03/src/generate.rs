@@ -0,0 +1,252 @@
+use common::{Anonymize, Generate};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use crate::{schematic::TokenType, Day03};
+
+const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '@', '=', '%', '&', '-'];
+
+/// A `size` x `size` schematic of blanks, numbers of up to three digits and symbols.
+impl Generate for Day03 {
+    fn generate(rng: &mut StdRng, size: usize) -> String {
+        let size = size.max(1);
+        render(&fill(rng, size, size, 0.25, 0.1, &[1, 2, 3]))
+    }
+}
+
+/// Rows of blanks, numbers with one of the `digits` lengths and symbols. At each cell that is not
+/// part of a number, a number starts with chance `number` and a symbol is placed with chance `symbol`.
+fn fill(rng: &mut StdRng, rows: usize, cols: usize, number: f64, symbol: f64, digits: &[usize]) -> Vec<Vec<char>> {
+    let mut grid = Vec::with_capacity(rows);
+    for _ in 0..rows {
+        let mut row = Vec::with_capacity(cols);
+        while row.len() < cols {
+            let roll: f64 = rng.gen();
+            if roll < number {
+                let length = digits.choose(rng).copied().unwrap_or(1).min(cols - row.len());
+                row.push(char::from(b'0' + rng.gen_range(1..10)));
+                for _ in 1..length {
+                    row.push(char::from(b'0' + rng.gen_range(0..10)));
+                }
+                // Keep neighbouring numbers apart so the token boundaries are the ones generated
+                if row.len() < cols {
+                    row.push('.');
+                }
+            } else if roll < number + symbol {
+                row.push(*SYMBOLS.choose(rng).unwrap_or(&'*'));
+            } else {
+                row.push('.');
+            }
+        }
+        grid.push(row);
+    }
+    grid
+}
+
+fn render(grid: &[Vec<char>]) -> String {
+    grid.iter().map(|row| row.iter().collect::<String>() + "\n").collect()
+}
+
+/// The size of a schematic, how dense its numbers are and how its symbols relate to them.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    rows: usize,
+    cols: usize,
+    /// Chance that a number starts at a cell that is not part of another number.
+    number: f64,
+    /// Lengths of the numbers, sampled from.
+    digits: Vec<usize>,
+    /// Symbols per number.
+    symbols: f64,
+    /// Gears per number.
+    gears: f64,
+    /// Share of the symbols that are asterisks but not gears.
+    asterisks: f64,
+}
+
+/// A schematic of the same size with as many numbers per cell, numbers as long as the real ones,
+/// and as many symbols and gears per number. Symbols are placed next to random numbers, like in
+/// the puzzle, so about as many numbers are part numbers.
+impl Anonymize for Day03 {
+    type Profile = Profile;
+
+    fn profile(schematic: &Self::Input) -> Profile {
+        let (rows, cols) = schematic.size();
+        let digits: Vec<usize> = schematic.numbers().map(str::len).collect();
+        let numbers = digits.len().max(1) as f64;
+        let decisions = (rows * cols).saturating_sub(digits.iter().sum()).max(1) as f64;
+        let symbols = schematic.symbols();
+        let gears = schematic.gears.len();
+        let asterisks = symbols.iter().filter(|symbol| symbol.token_type == TokenType::Asterisk).count();
+        Profile {
+            rows,
+            cols,
+            number: digits.len() as f64 / decisions,
+            symbols: symbols.len() as f64 / numbers,
+            gears: gears as f64 / numbers,
+            asterisks: asterisks.saturating_sub(gears) as f64 / symbols.len().saturating_sub(gears).max(1) as f64,
+            digits,
+        }
+    }
+
+    fn synthesize(rng: &mut StdRng, profile: &Profile) -> String {
+        let mut grid = fill(rng, profile.rows, profile.cols, profile.number, 0.0, &profile.digits);
+        let numbers = numbers(&grid);
+        if numbers.is_empty() {
+            return render(&grid);
+        }
+        let count = |share: f64| (share * numbers.len() as f64).round() as usize;
+
+        // A gear is an asterisk next to exactly two numbers
+        let mut gears = 0;
+        for _ in 0..count(profile.gears) * 10 {
+            if gears == count(profile.gears) {
+                break;
+            }
+            let number = numbers.choose(rng).expect("there are numbers");
+            let mut candidates = blank_neighbours(&grid, number);
+            candidates.shuffle(rng);
+            if let Some((row, col)) = candidates.into_iter().find(|cell| adjacent_numbers(&numbers, *cell) == 2) {
+                grid[row][col] = '*';
+                gears += 1;
+            }
+        }
+
+        for _ in gears..count(profile.symbols) {
+            let number = numbers.choose(rng).expect("there are numbers");
+            if let Some(&(row, col)) = blank_neighbours(&grid, number).choose(rng) {
+                grid[row][col] = if rng.gen_bool(profile.asterisks.clamp(0.0, 1.0)) {
+                    '*'
+                } else {
+                    *SYMBOLS[1..].choose(rng).unwrap_or(&'#')
+                };
+            }
+        }
+        render(&grid)
+    }
+}
+
+/// A number as its row and its first and last column.
+type Span = (usize, usize, usize);
+
+fn numbers(grid: &[Vec<char>]) -> Vec<Span> {
+    let mut numbers = Vec::new();
+    for (row, cells) in grid.iter().enumerate() {
+        let mut col = 0;
+        while col < cells.len() {
+            if cells[col].is_ascii_digit() {
+                let start = col;
+                while col + 1 < cells.len() && cells[col + 1].is_ascii_digit() {
+                    col += 1;
+                }
+                numbers.push((row, start, col));
+            }
+            col += 1;
+        }
+    }
+    numbers
+}
+
+fn is_adjacent(&(row, first, last): &Span, (cell_row, cell_col): (usize, usize)) -> bool {
+    cell_row.abs_diff(row) <= 1 && cell_col + 1 >= first && cell_col <= last + 1
+}
+
+fn blank_neighbours(grid: &[Vec<char>], number: &Span) -> Vec<(usize, usize)> {
+    let (row, first, last) = *number;
+    (row.saturating_sub(1)..=row + 1)
+        .flat_map(|r| (first.saturating_sub(1)..=last + 1).map(move |c| (r, c)))
+        .filter(|&(r, c)| grid.get(r).and_then(|cells| cells.get(c)) == Some(&'.'))
+        .filter(|cell| is_adjacent(number, *cell))
+        .collect()
+}
+
+fn adjacent_numbers(numbers: &[Span], cell: (usize, usize)) -> usize {
+    numbers.iter().filter(|number| is_adjacent(number, cell)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{anonymize, samples, Solution};
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::schematic::Schematic;
+
+    fn numbers(input: &str) -> Vec<usize> {
+        input
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse().unwrap())
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_part_numbers_are_numbers_of_the_schematic(sample in samples::<Day03>(1..40)) {
+            let schematic = Day03::parse(&sample.input).unwrap();
+            prop_assert_eq!(sample.input.lines().count(), sample.size);
+            let all = numbers(&sample.input);
+            prop_assert!(schematic.part_numbers.len() <= all.len());
+            prop_assert!(schematic.part_numbers.iter().all(|pn| all.contains(&pn.0.value())));
+        }
+
+        #[test]
+        fn prop_no_symbols_no_parts(sample in samples::<Day03>(1..40)) {
+            let input: String = sample
+                .input
+                .chars()
+                .map(|c| if SYMBOLS.contains(&c) { '.' } else { c })
+                .collect();
+            let schematic = Day03::parse(&input).unwrap();
+            prop_assert!(schematic.part_numbers.is_empty());
+            prop_assert!(schematic.gears.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_anonymized_schematic_has_the_same_profile() {
+        let real = Day03::parse(&Day03::synthesize(&mut StdRng::seed_from_u64(3), &default_profile())).unwrap();
+        let synthetic = Day03::parse(&Day03::synthesize(&mut StdRng::seed_from_u64(4), &Day03::profile(&real))).unwrap();
+        let (real_profile, synthetic_profile) = (Day03::profile(&real), Day03::profile(&synthetic));
+        assert_eq!(synthetic.size(), (100, 100));
+        let close = |a: f64, b: f64| (a - b).abs() <= 0.15 * b;
+        assert!(
+            close(synthetic_profile.number, real_profile.number),
+            "{:?} vs {:?}",
+            synthetic_profile,
+            real_profile
+        );
+        assert!(
+            close(synthetic_profile.symbols, real_profile.symbols),
+            "{:?} vs {:?}",
+            synthetic_profile,
+            real_profile
+        );
+        assert!(
+            close(synthetic_profile.gears, real_profile.gears),
+            "{:?} vs {:?}",
+            synthetic_profile,
+            real_profile
+        );
+        let parts = |schematic: &Schematic| schematic.part_numbers.len() as f64 / schematic.numbers().count() as f64;
+        assert!(close(parts(&synthetic), parts(&real)), "{} vs {}", parts(&synthetic), parts(&real));
+    }
+
+    #[test]
+    fn test_anonymize_without_numbers() {
+        assert_eq!(anonymize::<Day03>("...\n.#.\n", 1).unwrap(), "...\n...\n");
+    }
+
+    /// Dense like the puzzle input: most numbers are part numbers and many asterisks are gears.
+    fn default_profile() -> Profile {
+        Profile {
+            rows: 100,
+            cols: 100,
+            number: 0.08,
+            digits: vec![1, 2, 3, 3, 3],
+            symbols: 0.6,
+            gears: 0.15,
+            asterisks: 0.1,
+        }
+    }
+}
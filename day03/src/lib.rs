@@ -0,0 +1,37 @@
+use std::fmt::Display;
+
+use common::{Error, Solution};
+use schematic::Schematic;
+
+mod generate;
+mod schematic;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Schematic;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Schematic::from_string(data)
+    }
+
+    fn part_one(schematic: &Self::Input) -> Result<impl Display, Error> {
+        Ok(schematic.part_numbers.iter().map(|pn| pn.0.value()).sum::<usize>())
+    }
+
+    fn part_two(schematic: &Self::Input) -> Result<impl Display, Error> {
+        Ok(schematic.gears.iter().map(|gear| gear.ratio()).sum::<usize>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::assert_answers;
+
+    use super::*;
+
+    #[test]
+    fn test_example_answers() {
+        assert_answers!(Day03, "example");
+    }
+}
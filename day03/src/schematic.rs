@@ -1,9 +1,12 @@
+use std::collections::HashSet;
+
+use common::{Error, Grid, Pos};
 
 const BLANK: char = '.';
 
 #[derive(Debug, Default)]
 pub struct Schematic {
-    data: Vec<Vec<char>>,
+    data: Grid<char>,
     number_tokens: Vec<NumberToken>,
     symbol_tokens: Vec<SymbolToken>,
     pub part_numbers: Vec<PartNumber>,
@@ -11,12 +14,26 @@ pub struct Schematic {
 }
 
 impl Schematic {
-    pub fn from_string(s: &str) -> Self {
-        let mut schematic = Schematic::from(s);
+    pub fn from_string(s: &str) -> Result<Self, Error> {
+        let mut schematic = Schematic::try_from(s)?;
         schematic.tokenize();
         schematic.find_part_numbers();
         schematic.find_gears();
-        schematic
+        Ok(schematic)
+    }
+
+    /// Number of rows and columns.
+    pub fn size(&self) -> (usize, usize) {
+        (self.data.height(), self.data.width())
+    }
+
+    /// The digits of every number, in reading order.
+    pub fn numbers(&self) -> impl Iterator<Item = &str> {
+        self.number_tokens.iter().map(|token| token.n_str.as_str())
+    }
+
+    pub fn symbols(&self) -> &[SymbolToken] {
+        &self.symbol_tokens
     }
 
     fn find_part_numbers(&mut self) {
@@ -40,50 +57,43 @@ impl Schematic {
             })
             .collect();
         for (i, pn1) in possible_gears.iter().enumerate() {
-            for j in (i + 1)..possible_gears.len() {
-                if pn1.0.symbol_token() == possible_gears[j].0.symbol_token() {
-                    self.gears
-                        .push(Gear::new(pn1.0.clone(), possible_gears[j].0.clone()));
+            for pn2 in possible_gears.iter().skip(i + 1) {
+                if pn1.0.symbol_token() == pn2.0.symbol_token() {
+                    self.gears.push(Gear::new(pn1.0.clone(), pn2.0.clone()));
                 }
             }
         }
     }
 
     fn is_next_to_symbol(&self, number_token: &NumberToken) -> Option<SymbolToken> {
-        for symbol_token in self.symbol_tokens.iter() {
-            let symbol_token: SymbolToken = symbol_token.clone();
-            if symbol_token.row >= number_token.row - 1 && symbol_token.row <= number_token.row + 1
-            {
-                if symbol_token.col >= number_token.col - 1
-                    && symbol_token.col <= number_token.col + number_token.length() as i64
-                {
-                    return Some(symbol_token.clone());
-                }
-            }
-        }
-        None
+        let adjacent: HashSet<Pos> = (number_token.col..number_token.col + number_token.length())
+            .flat_map(|col| self.data.neighbours8(Pos::new(number_token.row, col)))
+            .collect();
+        self.symbol_tokens
+            .iter()
+            .find(|symbol_token| adjacent.contains(&Pos::new(symbol_token.row, symbol_token.col)))
+            .cloned()
     }
 
     fn tokenize(&mut self) {
         let mut parse_state = ParseState::BetweenTokens;
         let mut current_token: NumberToken = NumberToken::default();
 
-        for (row_no, row_data) in self.data.iter().enumerate() {
+        for (row_no, row_data) in self.data.rows().enumerate() {
             for (col_no, col_data) in row_data.iter().enumerate() {
                 match parse_state {
                     ParseState::BetweenTokens => {
-                        if col_data.is_digit(10) {
+                        if col_data.is_ascii_digit() {
                             parse_state = ParseState::InNumberToken;
-                            current_token =
-                                NumberToken::new("".to_string(), row_no as i64, col_no as i64);
+                            current_token = NumberToken::new("".to_string(), row_no, col_no);
                             current_token.n_str.push(*col_data);
                             continue;
                         }
-                        if !(col_data.is_digit(10) || col_data == &BLANK) {
+                        if !(col_data.is_ascii_digit() || col_data == &BLANK) {
                             // Symbol token
                             self.symbol_tokens.push(SymbolToken::new(
-                                row_no as i64,
-                                col_no as i64,
+                                row_no,
+                                col_no,
                                 if *col_data == '*' {
                                     TokenType::Asterisk
                                 } else {
@@ -93,7 +103,7 @@ impl Schematic {
                         }
                     }
                     ParseState::InNumberToken => {
-                        if !col_data.is_digit(10) {
+                        if !col_data.is_ascii_digit() {
                             self.number_tokens.push(current_token);
                             parse_state = ParseState::BetweenTokens;
                             current_token = NumberToken::default();
@@ -101,8 +111,8 @@ impl Schematic {
                             if col_data != &BLANK {
                                 // Symbol token
                                 self.symbol_tokens.push(SymbolToken::new(
-                                    row_no as i64,
-                                    col_no as i64,
+                                    row_no,
+                                    col_no,
                                     if *col_data == '*' {
                                         TokenType::Asterisk
                                     } else {
@@ -117,24 +127,25 @@ impl Schematic {
                     }
                 }
             }
+
+            // A number that ends the row ends the token as well
+            if let ParseState::InNumberToken = parse_state {
+                self.number_tokens.push(current_token);
+                parse_state = ParseState::BetweenTokens;
+                current_token = NumberToken::default();
+            }
         }
     }
 }
 
-impl From<&str> for Schematic {
-    fn from(v: &str) -> Self {
-        let mut data: Vec<Vec<char>> = Vec::new();
-        for line in v.lines() {
-            let mut cv: Vec<char> = line.chars().collect();
-            // Wrap lines between '.'
-            cv.insert(0, BLANK);
-            cv.push(BLANK);
-            data.push(cv);
-        }
-        Self {
-            data,
+impl TryFrom<&str> for Schematic {
+    type Error = Error;
+
+    fn try_from(v: &str) -> Result<Self, Self::Error> {
+        Ok(Self {
+            data: v.parse()?,
             ..Default::default()
-        }
+        })
     }
 }
 
@@ -166,13 +177,13 @@ enum ParseState {
 #[derive(Debug, Default, Clone)]
 pub struct NumberToken {
     n_str: String,
-    row: i64,
-    col: i64,
+    row: usize,
+    col: usize,
     symbol_token: Option<SymbolToken>,
 }
 
 impl NumberToken {
-    fn new(n_str: String, row: i64, col: i64) -> Self {
+    fn new(n_str: String, row: usize, col: usize) -> Self {
         Self {
             n_str: n_str.clone(),
             row,
@@ -203,12 +214,12 @@ pub enum TokenType {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct SymbolToken {
     pub token_type: TokenType,
-    pub row: i64,
-    pub col: i64,
+    pub row: usize,
+    pub col: usize,
 }
 
 impl SymbolToken {
-    pub fn new(row: i64, col: i64, token_type: TokenType) -> Self {
+    pub fn new(row: usize, col: usize, token_type: TokenType) -> Self {
         SymbolToken {
             token_type,
             row,
@@ -220,22 +231,21 @@ impl SymbolToken {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use common::read_test_data;
-    use std::path::Path; // Bring `add` function into scope
+    use common::read_input;
 
     #[test]
     fn test_make_schematic() {
-        let example_data = read_test_data(Path::new("./example.dat")).unwrap();
-        let sc = Schematic::from(example_data.as_str());
-        assert_eq!(sc.data.len(), 10);
-        assert_eq!(sc.data[0].len(), 12);
-        assert_eq!(sc.data[4][3], '7');
+        let example_data = read_input!("example").unwrap();
+        let sc = Schematic::try_from(example_data.as_str()).unwrap();
+        assert_eq!(sc.data.height(), 10);
+        assert_eq!(sc.data.width(), 10);
+        assert_eq!(sc.data[Pos::new(4, 2)], '7');
     }
 
     #[test]
     fn test_part_numbers() {
-        let example_data = read_test_data(Path::new("./example.dat")).unwrap();
-        let mut sc = Schematic::from(example_data.as_str());
+        let example_data = read_input!("example").unwrap();
+        let mut sc = Schematic::try_from(example_data.as_str()).unwrap();
         sc.tokenize();
         sc.find_part_numbers();
         println!("Part Numbers: {:?}", sc.part_numbers);
@@ -251,8 +261,8 @@ mod tests {
 
     #[test]
     fn test_gears() {
-        let example_data = read_test_data(Path::new("./example.dat")).unwrap();
-        let mut sc = Schematic::from(example_data.as_str());
+        let example_data = read_input!("example").unwrap();
+        let mut sc = Schematic::try_from(example_data.as_str()).unwrap();
         sc.tokenize();
         sc.find_part_numbers();
         sc.find_gears();
@@ -268,17 +278,17 @@ mod tests {
 
     #[test]
     fn test_tokenize() {
-        let example_data = read_test_data(Path::new("./example.dat")).unwrap();
-        let mut sc = Schematic::from(example_data.as_str());
+        let example_data = read_input!("example").unwrap();
+        let mut sc = Schematic::try_from(example_data.as_str()).unwrap();
         sc.tokenize();
         assert_eq!(sc.number_tokens.len(), 10);
         assert_eq!(sc.symbol_tokens.len(), 6);
         assert_eq!(sc.number_tokens[4].value(), 617);
-        assert_eq!(sc.number_tokens[4].col, 1);
+        assert_eq!(sc.number_tokens[4].col, 0);
         assert_eq!(sc.number_tokens[4].row, 4);
         assert_eq!(sc.number_tokens[4].length(), 3);
         assert_eq!(sc.symbol_tokens[2].token_type, TokenType::Asterisk);
-        assert_eq!(sc.symbol_tokens[2].col, 4);
+        assert_eq!(sc.symbol_tokens[2].col, 3);
         assert_eq!(sc.symbol_tokens[2].row, 4);
     }
 
@@ -28,7 +28,7 @@ impl Deck {
     }
 
     pub fn number_of_cards(&self) -> i64 {
-        self.cards.iter().map(|card_with_count| card_with_count.count).reduce(|acc, count| acc + count).unwrap()
+        self.cards.iter().map(|card_with_count| card_with_count.count).sum()
     }
 }
 
@@ -52,7 +52,10 @@ impl CardWithCount {
 
 #[cfg(test)]
 mod tests {
+    use common::{cross_check, samples};
+
     use super::*;
+    use crate::Day04;
 
     #[test]
     fn test_deck() {
@@ -83,7 +86,43 @@ mod tests {
         assert_eq!(deck.number_of_cards(), 30);
     }
 
-    const TEST_CARDS: &'static str = r"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53 
+    /// Processes every copy of every card one by one.
+    fn count_by_copying(cards: &str) -> i64 {
+        let cards: Vec<Card> = cards.lines().map(|line| Card::from_card_string(line).unwrap()).collect();
+        let mut pending: Vec<usize> = (0..cards.len()).collect();
+        let mut total = 0;
+        while let Some(i) = pending.pop() {
+            total += 1;
+            let wins = cards[i].correct_numbers().len();
+            pending.extend((i + 1..=i + wins).filter(|j| *j < cards.len()));
+        }
+        total
+    }
+
+    fn count_with_deck(cards: &str) -> i64 {
+        let mut deck = Deck::new();
+        for line in cards.lines() {
+            deck.add(Card::from_card_string(line).unwrap());
+        }
+        deck.process_wins();
+        deck.number_of_cards()
+    }
+
+    #[test]
+    fn test_count_by_copying() {
+        assert_eq!(count_by_copying(TEST_CARDS), 30);
+    }
+
+    #[test]
+    fn test_process_wins_counts_every_copy() {
+        cross_check(
+            |sample| count_by_copying(&sample.input),
+            |sample| count_with_deck(&sample.input),
+            samples::<Day04>(1..17),
+        );
+    }
+
+    const TEST_CARDS: &str = r"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53 
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
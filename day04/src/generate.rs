@@ -0,0 +1,116 @@
+use common::{Anonymize, Generate};
+use rand::{rngs::StdRng, seq::index, Rng};
+
+use crate::Day04;
+
+/// `size` cards numbered from 1, with 1 to 10 winning and 1 to 25 played numbers out of 1..=99.
+impl Generate for Day04 {
+    fn generate(rng: &mut StdRng, size: usize) -> String {
+        (1..=size)
+            .map(|card_no| {
+                let (win_count, played_count) = (rng.gen_range(1..=10), rng.gen_range(1..=25));
+                let wins = numbers(rng, win_count);
+                let played = numbers(rng, played_count);
+                format!("Card {:>3}: {} | {}\n", card_no, wins, played)
+            })
+            .collect()
+    }
+}
+
+/// How many winning, played and matching numbers every card has, and the largest number.
+#[derive(Debug)]
+pub struct Profile {
+    cards: Vec<(usize, usize, usize)>,
+    max_number: usize,
+}
+
+/// The cards of the real input with fresh numbers. The matches per card stay in their order:
+/// shuffled, the copies of part 2 easily grow exponentially, while the real inputs keep them in
+/// check. So both answers are the real ones, but none of the numbers are.
+impl Anonymize for Day04 {
+    type Profile = Profile;
+
+    fn profile(cards: &Self::Input) -> Profile {
+        Profile {
+            cards: cards
+                .iter()
+                .map(|card| (card.wins.len(), card.played.len(), card.correct_numbers().len()))
+                .collect(),
+            max_number: cards
+                .iter()
+                .flat_map(|card| card.wins.iter().chain(&card.played))
+                .copied()
+                .max()
+                .unwrap_or(1),
+        }
+    }
+
+    fn synthesize(rng: &mut StdRng, profile: &Profile) -> String {
+        profile
+            .cards
+            .iter()
+            .enumerate()
+            .map(|(i, &(win_count, played_count, matches))| {
+                // The first `matches` numbers are on both sides
+                let distinct = win_count + played_count - matches;
+                let numbers: Vec<usize> = index::sample(rng, profile.max_number.max(distinct), distinct)
+                    .iter()
+                    .map(|i| i + 1)
+                    .collect();
+                let wins = numbers[..win_count].to_vec();
+                let played: Vec<usize> = numbers[..matches].iter().chain(&numbers[win_count..]).copied().collect();
+                format!("Card {:>3}: {} | {}\n", i + 1, aligned(&wins), aligned(&played))
+            })
+            .collect()
+    }
+}
+
+fn aligned(numbers: &[usize]) -> String {
+    let numbers: Vec<String> = numbers.iter().map(|n| format!("{:>2}", n)).collect();
+    numbers.join(" ")
+}
+
+/// `amount` distinct numbers, right aligned like in the puzzle input.
+fn numbers(rng: &mut StdRng, amount: usize) -> String {
+    let numbers: Vec<String> = index::sample(rng, 99, amount).iter().map(|i| format!("{:>2}", i + 1)).collect();
+    numbers.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{samples, Solution};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::deck::Deck;
+
+    proptest! {
+        #[test]
+        fn prop_deck_never_shrinks(sample in samples::<Day04>(1..60)) {
+            let cards = Day04::parse(&sample.input).unwrap();
+            prop_assert_eq!(cards.len(), sample.size);
+            let mut deck = Deck::new();
+            for card in &cards {
+                deck.add(card.clone());
+            }
+            deck.process_wins();
+            prop_assert!(deck.number_of_cards() >= cards.len() as i64);
+        }
+
+        #[test]
+        fn prop_score_is_bounded_by_the_winning_numbers(sample in samples::<Day04>(1..60)) {
+            for card in Day04::parse(&sample.input).unwrap() {
+                prop_assert!(card.correct_numbers().len() <= card.wins.len().min(card.played.len()));
+                prop_assert!(card.score() <= 1 << (card.wins.len() - 1));
+            }
+        }
+
+        #[test]
+        fn prop_anonymized_cards_have_the_same_matches(sample in samples::<Day04>(1..60)) {
+            let real = Day04::parse(&sample.input).unwrap();
+            let synthetic = Day04::parse(&sample.anonymize::<Day04>().unwrap()).unwrap();
+            prop_assert_eq!(Day04::profile(&real).cards, Day04::profile(&synthetic).cards);
+            prop_assert_eq!(Day04::part_two(&real).unwrap().to_string(), Day04::part_two(&synthetic).unwrap().to_string());
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt::Display;
+
+use card::Card;
+use common::{parse_lines, Error, Solution};
+use deck::Deck;
+
+mod card;
+mod deck;
+mod generate;
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Card>;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        parse_lines(data, Card::from_card_string)
+    }
+
+    fn part_one(cards: &Self::Input) -> Result<impl Display, Error> {
+        Ok(cards.iter().map(|card| card.score()).sum::<usize>())
+    }
+
+    fn part_two(cards: &Self::Input) -> Result<impl Display, Error> {
+        let mut deck = Deck::new();
+        for card in cards {
+            deck.add(card.clone());
+        }
+        deck.process_wins();
+        Ok(deck.number_of_cards())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::assert_answers;
+
+    use super::*;
+
+    #[test]
+    fn test_example_answers() {
+        assert_answers!(Day04, "example");
+    }
+}
@@ -0,0 +1,172 @@
+use common::{Anonymize, Generate};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+use crate::Day05;
+
+const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+/// An almanac with `size` seed ranges and `size` entries per map.
+///
+/// Like in the puzzle, every map permutes the ranges it covers, so it is a bijection.
+impl Generate for Day05 {
+    fn generate(rng: &mut StdRng, size: usize) -> String {
+        let size = size.max(1);
+        let seeds: Vec<String> = (0..size)
+            .map(|_| format!("{} {}", rng.gen_range(0..size as i64 * 12), rng.gen_range(1..=10)))
+            .collect();
+        let mut out = format!("seeds: {}\n", seeds.join(" "));
+        for name in MAP_NAMES {
+            out += &format!("\n{} map:\n", name);
+            let base = rng.gen_range(0..20);
+            let lengths: Vec<i64> = (0..size).map(|_| rng.gen_range(1..=10)).collect();
+            for (target, source, length) in permuted_ranges(rng, base, &lengths) {
+                out += &format!("{} {} {}\n", target, source, length);
+            }
+        }
+        out
+    }
+}
+
+/// The lengths of the seed ranges and of the entries of every map, and the largest value.
+#[derive(Debug)]
+pub struct Profile {
+    seed_ranges: Vec<i64>,
+    maps: Vec<(String, Vec<i64>)>,
+    span: i64,
+}
+
+/// Seed ranges and map entries as long as the real ones, at random places below the largest
+/// real value. The total length of the seed ranges, which the brute force of part 2 walks, is
+/// the same as in the real input.
+impl Anonymize for Day05 {
+    type Profile = Profile;
+
+    fn profile(almanac: &Self::Input) -> Profile {
+        let seeds = almanac.seeds.chunks_exact(2).map(|range| range[0] + range[1]);
+        let entries = almanac
+            .map_chain
+            .maps
+            .iter()
+            .flat_map(|map| map.map_entries())
+            .map(|entry| entry.source_start.max(entry.target_start) + entry.range_length);
+        Profile {
+            seed_ranges: almanac.seeds.chunks_exact(2).map(|range| range[1]).collect(),
+            maps: almanac
+                .map_chain
+                .maps
+                .iter()
+                .map(|map| {
+                    (
+                        map.name().to_string(),
+                        map.map_entries().iter().map(|entry| entry.range_length).collect(),
+                    )
+                })
+                .collect(),
+            span: seeds.chain(entries).max().unwrap_or_default(),
+        }
+    }
+
+    fn synthesize(rng: &mut StdRng, profile: &Profile) -> String {
+        let mut seed_ranges = profile.seed_ranges.clone();
+        seed_ranges.shuffle(rng);
+        let seeds: Vec<String> = seed_ranges
+            .iter()
+            .map(|length| format!("{} {}", rng.gen_range(0..=(profile.span - length).max(0)), length))
+            .collect();
+        let mut out = format!("seeds: {}\n", seeds.join(" "));
+        for (name, lengths) in &profile.maps {
+            out += &format!("\n{} map:\n", name);
+            let mut lengths = lengths.clone();
+            lengths.shuffle(rng);
+            let base = rng.gen_range(0..=(profile.span - lengths.iter().sum::<i64>()).max(0));
+            for (target, source, length) in permuted_ranges(rng, base, &lengths) {
+                out += &format!("{} {} {}\n", target, source, length);
+            }
+        }
+        out
+    }
+}
+
+/// Lays out ranges of the given lengths from `base` on and moves each onto a slot of a shuffled
+/// layout of the same span.
+fn permuted_ranges(rng: &mut StdRng, base: i64, lengths: &[i64]) -> Vec<(i64, i64, i64)> {
+    let count = lengths.len();
+    let mut order: Vec<usize> = (0..count).collect();
+    order.shuffle(rng);
+
+    let starts = |order: &[usize]| {
+        let mut starts = vec![0; count];
+        let mut next = base;
+        for &i in order {
+            starts[i] = next;
+            next += lengths[i];
+        }
+        starts
+    };
+    let sources = starts(&(0..count).collect::<Vec<_>>());
+    let targets = starts(&order);
+    (0..count).map(|i| (targets[i], sources[i], lengths[i])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, str::FromStr};
+
+    use common::{samples, Solution};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{get_seeds, MapChain};
+
+    proptest! {
+        #[test]
+        fn prop_maps_are_bijections_on_covered_ranges(sample in samples::<Day05>(1..30)) {
+            let map_chain = MapChain::from_str(&sample.input).unwrap();
+            prop_assert_eq!(map_chain.maps.len(), MAP_NAMES.len());
+            for map in &map_chain.maps {
+                let covered: BTreeSet<i64> = map
+                    .map_entries()
+                    .iter()
+                    .flat_map(|entry| entry.source_start..entry.source_start + entry.range_length)
+                    .collect();
+                let image: BTreeSet<i64> = covered.iter().map(|v| map.map(*v)).collect();
+                prop_assert_eq!(image, covered);
+            }
+        }
+
+        #[test]
+        fn prop_chain_is_a_bijection(sample in samples::<Day05>(1..30)) {
+            let map_chain = MapChain::from_str(&sample.input).unwrap();
+            // Every map covers at most 20 + 10 * size values from 0 on
+            let domain: BTreeSet<i64> = (0..20 + 10 * sample.size as i64).collect();
+            let image: BTreeSet<i64> = domain.iter().map(|v| map_chain.map(*v)).collect();
+            prop_assert_eq!(image, domain);
+            prop_assert_eq!(get_seeds(&sample.input).unwrap().len(), 2 * sample.size);
+        }
+
+        #[test]
+        fn prop_anonymized_almanac_has_the_same_ranges(sample in samples::<Day05>(1..30)) {
+            let real = Day05::profile(&Day05::parse(&sample.input).unwrap());
+            let synthetic = Day05::profile(&Day05::parse(&sample.anonymize::<Day05>().unwrap()).unwrap());
+            let sorted = |mut lengths: Vec<i64>| {
+                lengths.sort_unstable();
+                lengths
+            };
+            prop_assert_eq!(sorted(synthetic.seed_ranges), sorted(real.seed_ranges));
+            prop_assert_eq!(synthetic.maps.len(), real.maps.len());
+            for ((name, lengths), (real_name, real_lengths)) in synthetic.maps.into_iter().zip(real.maps) {
+                prop_assert_eq!(name, real_name);
+                prop_assert_eq!(sorted(lengths), sorted(real_lengths));
+            }
+            prop_assert!(synthetic.span <= real.span);
+        }
+    }
+}
@@ -0,0 +1,527 @@
+use std::{fmt::Display, path::Path, str::FromStr, sync::Arc};
+
+use common::{
+    checkpoint::{self, Checkpoint, State},
+    checkpoint_dir, parse_lines,
+    parser::{integer, keyword, lexeme, many1, map, parse_all, preceded, tag, ws},
+    progress::Progress,
+    Error, Solution,
+};
+use tokio::task::JoinSet;
+use tracing::{debug, info, Instrument, Span};
+
+mod generate;
+
+pub struct Day05;
+
+/// The parsed almanac: the seed line and the chain of maps from seed to location.
+pub struct Almanac {
+    seeds: Vec<i64>,
+    map_chain: MapChain,
+}
+
+impl Solution for Day05 {
+    type Input = Almanac;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(Almanac {
+            seeds: get_seeds(data)?,
+            map_chain: MapChain::from_str(data)?,
+        })
+    }
+
+    fn part_one(almanac: &Self::Input) -> Result<impl Display, Error> {
+        let locations: Vec<i64> = almanac.seeds.iter().map(|seed| almanac.map_chain.map(*seed)).collect();
+        debug!(?locations, "Mapped the seeds to locations");
+        locations
+            .into_iter()
+            .min()
+            .ok_or_else(|| Error::validation("The almanac contains no seeds"))
+    }
+
+    fn part_two(almanac: &Self::Input) -> Result<impl Display, Error> {
+        almanac
+            .map_chain
+            .min_location_of_ranges(&almanac.seeds)
+            .ok_or_else(|| Error::validation("The almanac contains no seed ranges"))
+    }
+}
+
+impl Almanac {
+    /// The answer of part two found by mapping every single seed, which takes minutes on a real
+    /// almanac. The search is checkpointed in `checkpoint_dir`, so after Ctrl-C it resumes where
+    /// it stopped.
+    pub fn min_location_by_seed(&self, checkpoint_dir: &Path) -> Result<i64, Error> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(min_location_of_seed_ranges(&self.seeds, &self.map_chain, checkpoint_dir))
+    }
+}
+
+/// Answers part two of `data` with [`Almanac::min_location_by_seed`], checkpointing in the
+/// workspace's checkpoint directory, for `advoc run 5 --brute-force`.
+pub fn brute_force(data: &str) -> Result<String, Error> {
+    let almanac = Day05::parse(data)?;
+    Ok(almanac.min_location_by_seed(&checkpoint_dir!())?.to_string())
+}
+
+/// Seeds mapped by a task between two checkpoint updates.
+const CHUNK: i64 = 1 << 20;
+
+/// How far the search of one seed range got, and the lowest location found in it so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RangeProgress {
+    next: i64,
+    end: i64,
+    min_loc: i64,
+}
+
+/// The progress of the search of every seed range.
+#[derive(Debug, Clone, PartialEq)]
+struct SeedSearch {
+    ranges: Vec<RangeProgress>,
+}
+
+impl SeedSearch {
+    fn new(seeds: &[i64]) -> Self {
+        let ranges = seeds
+            .chunks_exact(2)
+            .map(|pair| RangeProgress {
+                next: pair[0],
+                end: pair[0] + pair[1],
+                min_loc: i64::MAX,
+            })
+            .collect();
+        Self { ranges }
+    }
+
+    fn remaining(&self) -> i64 {
+        self.ranges.iter().map(|range| range.end - range.next).sum()
+    }
+}
+
+/// One line per seed range: the next seed to map, the end of the range and the lowest location.
+impl State for SeedSearch {
+    fn encode(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|range| format!("{} {} {}\n", range.next, range.end, range.min_loc))
+            .collect()
+    }
+
+    fn decode(text: &str) -> Result<Self, Error> {
+        let ranges = parse_lines(text, |line| {
+            match line.split(' ').map(str::parse).collect::<Result<Vec<i64>, _>>() {
+                Ok(numbers) if numbers.len() == 3 => Ok(RangeProgress {
+                    next: numbers[0],
+                    end: numbers[1],
+                    min_loc: numbers[2],
+                }),
+                _ => Err(Error::parse("Expected the next seed, the end of the range and the lowest location")),
+            }
+        })?;
+        Ok(Self { ranges })
+    }
+}
+
+async fn min_location_of_seed_ranges(seeds: &[i64], map_chain: &MapChain, checkpoint_dir: &Path) -> Result<i64, Error> {
+    let key = format!("{:?} {:?}", seeds, map_chain);
+    let checkpoint = Checkpoint::open(checkpoint_dir, "day05-part2", &key, || SeedSearch::new(seeds))?;
+    let checkpoint = Arc::new(checkpoint);
+    let seed_count = checkpoint.read(SeedSearch::remaining);
+    info!(seed_count, resumed = checkpoint.resumed(), "Searching the seed ranges");
+    let progress = Progress::new("seeds");
+    let mut join_set = JoinSet::new();
+
+    for (i, (range, pair)) in checkpoint
+        .read(|search| search.ranges.clone())
+        .into_iter()
+        .zip(seeds.chunks_exact(2))
+        .enumerate()
+    {
+        let map_chain = map_chain.clone();
+        let checkpoint = Arc::clone(&checkpoint);
+        let bar = progress
+            .bar(format!("range {}", i + 1), pair[1] as u64)
+            .with_position((pair[1] - (range.end - range.next)) as u64);
+        join_set.spawn(
+            async move {
+                let mut min_loc = range.min_loc;
+                let mut next = range.next;
+                while next < range.end && !checkpoint::interrupted() {
+                    let chunk_end = range.end.min(next + CHUNK);
+                    for seed in next..chunk_end {
+                        min_loc = min_loc.min(map_chain.map(seed));
+                    }
+                    bar.inc((chunk_end - next) as u64);
+                    next = chunk_end;
+                    checkpoint.update(|search| search.ranges[i] = RangeProgress { next, min_loc, ..range })?;
+                }
+                if next == range.end {
+                    bar.finish();
+                }
+                Ok::<_, Error>(min_loc)
+            }
+            .instrument(Span::current()),
+        );
+    }
+
+    let mut min_loc = i64::MAX;
+    while let Some(result) = join_set.join_next().await {
+        min_loc = min_loc.min(result.map_err(|e| Error::other(e.to_string()).with_source(e))??);
+    }
+    progress.finish();
+
+    if checkpoint::interrupted() {
+        checkpoint.save()?;
+        let remaining = checkpoint.read(SeedSearch::remaining);
+        return Err(Error::other(format!(
+            "Interrupted with {} of {} seeds left; run again to resume from {}",
+            remaining,
+            seed_count,
+            checkpoint.path().display()
+        )));
+    }
+    Arc::into_inner(checkpoint).expect("every task has finished").finish()?;
+    Ok(min_loc)
+}
+
+/// The numbers of the `seeds: ` line of the almanac.
+pub fn get_seeds(data: &str) -> Result<Vec<i64>, Error> {
+    let (index, line) = data
+        .lines()
+        .enumerate()
+        .find(|(_, line)| line.starts_with(SEED_MARKER))
+        .ok_or_else(|| Error::parse("The almanac has no seeds line"))?;
+    let seeds = preceded((keyword("seeds"), lexeme(tag(":"))), many1(lexeme(integer::<i64>)));
+    parse_all(&seeds, line).map_err(|e| e.with_line(index + 1, line))
+}
+
+const SEED_MARKER: &str = "seeds: ";
+const MAP_MARKER: &str = " map:";
+
+#[derive(Debug, Default, Clone)]
+pub struct MapChain {
+    maps: Vec<NamedMap>,
+}
+
+impl MapChain {
+    pub fn map(&self, v: i64) -> i64 {
+        // println!("Start: {}", v);
+        let mut map_staged = v;
+        for map in &self.maps {
+            map_staged = map.map(map_staged);
+            // println!("  Map {}: -> {}", map.name(), map_staged);
+        }
+        map_staged
+    }
+
+    /// The lowest location of any seed in the `(start, length)` pairs of `seeds`, found by mapping
+    /// whole ranges instead of single seeds. Cross-checked against mapping every seed.
+    pub fn min_location_of_ranges(&self, seeds: &[i64]) -> Option<i64> {
+        let mut ranges: Vec<(i64, i64)> = seeds.chunks_exact(2).map(|pair| (pair[0], pair[0] + pair[1])).collect();
+        for map in &self.maps {
+            ranges = ranges.into_iter().flat_map(|(start, end)| map.map_range(start, end)).collect();
+        }
+        ranges.into_iter().filter(|(start, end)| start < end).map(|(start, _)| start).min()
+    }
+}
+
+impl FromStr for MapChain {
+    type Err = Error;
+
+    fn from_str(data: &str) -> Result<Self, Error> {
+        let mut map_chain = MapChain::default();
+        let mut parse_state = ParseState::BlankLine;
+        let mut current_raw_map = NamedMap::default();
+
+        // add a blank line to properly let the parser finish the last block
+        for (index, line) in data.lines().chain(vec!["", ""]).enumerate() {
+            match parse_state {
+                ParseState::BlankLine => {
+                    if line.starts_with(SEED_MARKER) {
+                        parse_state = ParseState::BlankLine;
+                    }
+                    if line.contains(MAP_MARKER) {
+                        current_raw_map = NamedMap::default();
+                        current_raw_map.name = line.split(" ").next().unwrap().to_owned();
+                        parse_state = ParseState::MapBlock;
+                    }
+                }
+                ParseState::MapBlock => {
+                    if line.is_empty() {
+                        map_chain.maps.push(current_raw_map.clone());
+                        current_raw_map = NamedMap::default();
+                        parse_state = ParseState::BlankLine;
+                    } else {
+                        let entry = MapEntry::from_str(line).map_err(|e| e.with_line(index + 1, line))?;
+                        current_raw_map.map_entries.push(entry);
+                    }
+                }
+                ParseState::SeedLine => {}
+            }
+        }
+        Ok(map_chain)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct NamedMap {
+    name: String,
+    map_entries: Vec<MapEntry>,
+}
+
+impl NamedMap {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn map_entries(&self) -> &[MapEntry] {
+        self.map_entries.as_slice()
+    }
+
+    /// Maps the half-open range `start..end` to the ranges its values land on.
+    pub fn map_range(&self, start: i64, end: i64) -> Vec<(i64, i64)> {
+        let mut unmapped = vec![(start, end)];
+        let mut mapped = Vec::new();
+        for entry in &self.map_entries {
+            let source_end = entry.source_start + entry.range_length;
+            let offset = entry.target_start - entry.source_start;
+            let mut rest = Vec::new();
+            for (start, end) in unmapped {
+                let (low, high) = (start.max(entry.source_start), end.min(source_end));
+                if low < high {
+                    mapped.push((low + offset, high + offset));
+                    rest.extend([(start, low), (high, end)].into_iter().filter(|(start, end)| start < end));
+                } else {
+                    rest.push((start, end));
+                }
+            }
+            unmapped = rest;
+        }
+        mapped.extend(unmapped);
+        mapped
+    }
+
+    pub fn map(&self, v: i64) -> i64 {
+        for map_entry in &self.map_entries {
+            let mapped_value = map_entry.map(v);
+            if mapped_value != v {
+                return mapped_value;
+            }
+        }
+        v
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseState {
+    BlankLine,
+    SeedLine,
+    MapBlock,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MapEntry {
+    target_start: i64,
+    source_start: i64,
+    range_length: i64,
+}
+
+impl MapEntry {
+    pub fn map(&self, v: i64) -> i64 {
+        if v >= self.source_start && v < self.source_start + self.range_length {
+            v + self.target_start - self.source_start
+        } else {
+            v
+        }
+    }
+}
+
+/// A line like `50 98 2`: the target start, the source start and the length of the range.
+impl FromStr for MapEntry {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Error> {
+        let number = || lexeme(integer::<i64>);
+        let entry = map(
+            (preceded(ws, number()), number(), number()),
+            |(target_start, source_start, range_length)| MapEntry {
+                target_start,
+                source_start,
+                range_length,
+            },
+        );
+        parse_all(&entry, line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{assert_answers, cross_check, read_input, samples};
+
+    #[test]
+    fn test_example_answers() {
+        assert_answers!(Day05, "example");
+    }
+
+    /// Maps every seed of every range.
+    fn min_location_by_seed(almanac: &str) -> Option<i64> {
+        let map_chain = MapChain::from_str(almanac).unwrap();
+        get_seeds(almanac)
+            .unwrap()
+            .chunks_exact(2)
+            .flat_map(|pair| pair[0]..pair[0] + pair[1])
+            .map(|seed| map_chain.map(seed))
+            .min()
+    }
+
+    #[test]
+    fn test_min_location_of_ranges() {
+        let testdata = read_input!("testdata").unwrap();
+        assert_eq!(
+            MapChain::from_str(&testdata)
+                .unwrap()
+                .min_location_of_ranges(&get_seeds(&testdata).unwrap()),
+            Some(34039469)
+        );
+        let example = read_input!("example").unwrap();
+        let map_chain = MapChain::from_str(&example).unwrap();
+        assert_eq!(
+            map_chain.min_location_of_ranges(&get_seeds(&example).unwrap()),
+            min_location_by_seed(&example)
+        );
+    }
+
+    /// Maps the seed ranges as a whole.
+    fn min_location_of_ranges(almanac: &str) -> Option<i64> {
+        let map_chain = MapChain::from_str(almanac).unwrap();
+        map_chain.min_location_of_ranges(&get_seeds(almanac).unwrap())
+    }
+
+    #[test]
+    fn test_min_location_of_ranges_maps_every_seed() {
+        cross_check(
+            |sample| min_location_by_seed(&sample.input),
+            |sample| min_location_of_ranges(&sample.input),
+            samples::<Day05>(1..17),
+        );
+    }
+
+    #[test]
+    fn test_resume_seed_search() {
+        let example = read_input!("example").unwrap();
+        let almanac = example.replacen("seeds: 79 14 55 13", "seeds: 79 14", 1);
+        let almanac = Day05::parse(&almanac).unwrap();
+        let search = SeedSearch::new(&almanac.seeds);
+        assert_eq!(SeedSearch::decode(&search.encode()).unwrap(), search);
+
+        // A checkpoint claiming the range is done with a made-up minimum is taken at its word.
+        let dir = std::env::temp_dir().join(format!("day05-checkpoint-{}", std::process::id()));
+        let key = format!("{:?} {:?}", almanac.seeds, almanac.map_chain);
+        let checkpoint = Checkpoint::open(&dir, "day05-part2", &key, || search).unwrap();
+        checkpoint
+            .update(|search| {
+                search.ranges[0] = RangeProgress {
+                    next: 93,
+                    end: 93,
+                    min_loc: 1,
+                }
+            })
+            .unwrap();
+        checkpoint.save().unwrap();
+        let path = checkpoint.path().to_path_buf();
+        drop(checkpoint);
+        assert_eq!(almanac.min_location_by_seed(&dir).unwrap(), 1);
+        assert!(!path.exists());
+
+        // Without a checkpoint the whole range is searched
+        assert_eq!(almanac.min_location_by_seed(&dir).unwrap(), 46);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_map_chain() {
+        let test_data = read_input!("example").unwrap();
+        let map_chain = MapChain::from_str(&test_data).unwrap();
+        let seeds = get_seeds(&test_data).unwrap();
+        let locations: Vec<i64> = seeds.into_iter().map(|seed| map_chain.map(seed)).collect();
+        let min = locations.iter().reduce(|acc, v| if v < acc { v } else { acc }).unwrap();
+        assert_eq!(map_chain.map(79), 82);
+        println!("Locations: {:?}", locations);
+        println!("  Minimum location: {}", min);
+        assert_eq!(map_chain.map(14), 43);
+        assert_eq!(map_chain.map(55), 86);
+        assert_eq!(map_chain.map(13), 35);
+    }
+
+    #[test]
+    fn test_get_seeds() {
+        let test_data = read_input!("example").unwrap();
+        let seeds = get_seeds(&test_data).unwrap();
+        println!("Seeds: {:?}", seeds);
+        assert_eq!(seeds.len(), 4);
+        for i in (0..seeds.len()).step_by(2) {
+            for j in seeds[i]..(seeds[i] + seeds[i + 1]) {
+                println!("seed: {}", j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_water_map() {
+        let test_data = read_input!("example").unwrap();
+        let maps = MapChain::from_str(&test_data).unwrap().maps;
+        assert_eq!(maps[2].name(), "fertilizer-to-water");
+        assert_eq!(maps[2].map(53), 49);
+    }
+
+    #[test]
+    fn test_named_map() {
+        let test_data = read_input!("example").unwrap();
+        let maps = MapChain::from_str(&test_data).unwrap().maps;
+        assert_eq!(maps.len(), 7);
+        assert_eq!(maps[1].name(), "soil-to-fertilizer");
+        assert_eq!(maps[2].map_entries().len(), 4);
+        assert_eq!(maps[2].map_entries()[2].target_start, 42);
+
+        assert_eq!(maps[0].map(14), 14);
+        assert_eq!(maps[0].map(79), 81);
+        assert_eq!(maps[0].map(55), 57);
+        assert_eq!(maps[0].map(13), 13);
+    }
+
+    #[test]
+    fn test_map_entry() {
+        let map = MapEntry::from_str("50 98 2").unwrap();
+        assert_eq!(
+            map,
+            MapEntry {
+                target_start: 50,
+                source_start: 98,
+                range_length: 2,
+            }
+        );
+
+        assert_eq!(map.map(97), 97);
+        assert_eq!(map.map(98), 50);
+        assert_eq!(map.map(99), 51);
+        assert_eq!(map.map(100), 100);
+    }
+
+    #[test]
+    fn test_malformed_almanac() {
+        let error = MapEntry::from_str("50 98 2 7").unwrap_err();
+        assert_eq!(error.column(), Some(9));
+        assert!(MapEntry::from_str("50 98").is_err());
+
+        let error = MapChain::from_str("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 x 48\n").unwrap_err();
+        assert_eq!(error.line(), Some(5));
+        assert_eq!(error.column(), Some(4));
+
+        let error = get_seeds("seeds: 79 14 5x\n").unwrap_err();
+        assert_eq!(error.line(), Some(1));
+        assert_eq!(error.column(), Some(15));
+        assert!(get_seeds("seed-to-soil map:\n50 98 2\n").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use common::{Anonymize, Generate};
+use rand::{rngs::StdRng, Rng};
+
+use crate::Day06;
+
+/// A sheet of `size` races, each of which can be won.
+///
+/// The kerned reading of part 2 concatenates all numbers, so it only fits an `i64` for a handful
+/// of races.
+impl Generate for Day06 {
+    fn generate(rng: &mut StdRng, size: usize) -> String {
+        let races: Vec<(i64, i64)> = (0..size.max(1))
+            .map(|_| {
+                let duration = rng.gen_range(2..100);
+                (duration, rng.gen_range(0..best_distance(duration)))
+            })
+            .collect();
+        sheet(&races)
+    }
+}
+
+/// For every race, the number of digits of its duration and how close its record comes to the
+/// best possible distance.
+#[derive(Debug)]
+pub struct Profile {
+    races: Vec<(u32, f64)>,
+}
+
+/// Races with durations of as many digits as the real ones and records as close to the best
+/// distance. The kerned race of part 2 has the same magnitude, and so has its number of ways.
+impl Anonymize for Day06 {
+    type Profile = Profile;
+
+    fn profile(sheet: &Self::Input) -> Profile {
+        let races = sheet
+            .season
+            .races
+            .iter()
+            .map(|race| {
+                let best = best_distance(race.duration).max(1);
+                (race.duration.max(1).ilog10() + 1, race.distance as f64 / best as f64)
+            })
+            .collect();
+        Profile { races }
+    }
+
+    fn synthesize(rng: &mut StdRng, profile: &Profile) -> String {
+        let races: Vec<(i64, i64)> = profile
+            .races
+            .iter()
+            .map(|(digits, ratio)| {
+                let duration = rng.gen_range(10_i64.pow(digits - 1).max(2)..10_i64.pow(*digits));
+                let best = best_distance(duration);
+                (duration, ((best as f64 * ratio) as i64).clamp(0, best - 1))
+            })
+            .collect();
+        sheet(&races)
+    }
+}
+
+/// Holding the button for half the race goes farthest.
+fn best_distance(duration: i64) -> i64 {
+    (duration / 2) * (duration - duration / 2)
+}
+
+/// The two rows of the sheet, with the numbers right aligned in columns.
+fn sheet(races: &[(i64, i64)]) -> String {
+    let widest = races
+        .iter()
+        .map(|(_, distance)| distance.to_string().len())
+        .max()
+        .unwrap_or_default();
+    let width = (widest + 1).max(5);
+    let row = |numbers: Vec<i64>| numbers.iter().map(|n| format!("{:>width$}", n)).collect::<String>();
+    format!(
+        "{:<9}{}\n{:<9}{}\n",
+        "Time:",
+        row(races.iter().map(|race| race.0).collect()),
+        "Distance:",
+        row(races.iter().map(|race| race.1).collect())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use common::{samples, Solution};
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::Season;
+
+    proptest! {
+        #[test]
+        fn prop_win_limits_lie_within_the_race(sample in samples::<Day06>(1..20)) {
+            let season = Season::from_str(&sample.input).unwrap();
+            prop_assert_eq!(season.races.len(), sample.size);
+            for race in &season.races {
+                let limits = race.calc_win_limits();
+                prop_assert!(limits.0 <= limits.1, "{:?} has no way to win: {:?}", race, limits);
+                prop_assert!(limits.0 >= 1 && limits.1 < race.duration, "{:?}: {:?}", race, limits);
+            }
+        }
+
+        #[test]
+        fn prop_anonymized_races_have_the_same_magnitude(sample in samples::<Day06>(1..5)) {
+            let real = Day06::profile(&Day06::parse(&sample.input).unwrap());
+            let synthetic = Day06::parse(&sample.anonymize::<Day06>().unwrap()).unwrap();
+            prop_assert_eq!(synthetic.season.races.len(), sample.size);
+            for (race, (digits, _)) in synthetic.season.races.iter().zip(&real.races) {
+                prop_assert_eq!(race.duration.ilog10() + 1, *digits);
+                prop_assert!(race.calc_win_combinatios().0 >= 1, "{:?} cannot be won", race);
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{fmt::Display, str::FromStr};
+
+use common::{
+    parser::{integer, keyword, lexeme, many0, parse_all, preceded, tag, ws},
+    *,
+};
+
+mod generate;
+
+pub struct Day06;
+
+/// The race sheet read both ways: as separate races (part 1) and with the kerning removed (part 2).
+pub struct RaceSheet {
+    season: Season,
+    kerned_season: Season,
+}
+
+impl Solution for Day06 {
+    type Input = RaceSheet;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Ok(RaceSheet {
+            season: Season::from_str(data)?,
+            kerned_season: Season::from_str2(data)?,
+        })
+    }
+
+    fn part_one(sheet: &Self::Input) -> Result<impl Display, Error> {
+        Ok(sheet.season.winning_combinations())
+    }
+
+    fn part_two(sheet: &Self::Input) -> Result<impl Display, Error> {
+        Ok(sheet.kerned_season.winning_combinations())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Season {
+    races: Vec<Race>,
+}
+
+impl Season {
+    pub fn add(&mut self, race: Race) {
+        self.races.push(race);
+    }
+
+    pub fn winning_combinations(&self) -> i64 {
+        self.races
+            .iter()
+            .map(|race| race.calc_win_combinatios().0)
+            .reduce(|acc, v| acc * v)
+            .unwrap()
+    }
+
+    pub fn from_str2(data: &str) -> Result<Season, Error> {
+        let (times, distances) = race_sheet(data)?;
+        let kerned = |numbers: Vec<i64>| numbers.iter().map(i64::to_string).collect::<String>();
+
+        let mut season = Season::default();
+        season.add(Race {
+            duration: kerned(times).parse()?,
+            distance: kerned(distances).parse()?,
+        });
+        Ok(season)
+    }
+}
+
+impl FromStr for Season {
+    type Err = Error;
+
+    fn from_str(data: &str) -> Result<Season, Error> {
+        let (times, distances) = race_sheet(data)?;
+
+        let mut season = Season::default();
+        for (duration, distance) in times.into_iter().zip(distances) {
+            season.add(Race { duration, distance })
+        }
+        Ok(season)
+    }
+}
+
+/// The times and the record distances of the sheet, one of each per race.
+fn race_sheet(data: &str) -> Result<(Vec<i64>, Vec<i64>), Error> {
+    let mut lines = data.lines();
+    let time_line = lines.next().unwrap_or_default();
+    let times = sheet_line("Time", time_line).map_err(|e| e.with_line(1, time_line))?;
+    let distance_line = lines.next().unwrap_or_default();
+    let distances = sheet_line("Distance", distance_line).map_err(|e| e.with_line(2, distance_line))?;
+
+    if distances.len() != times.len() {
+        let message = format!("Expected {} distances, one per time, found {}", times.len(), distances.len());
+        return Err(Error::parse(message).with_line(2, distance_line));
+    }
+    Ok((times, distances))
+}
+
+/// The numbers of a sheet line like `Time:      7  15   30`.
+fn sheet_line(label: &'static str, line: &str) -> Result<Vec<i64>, Error> {
+    let header = (ws, lexeme(keyword(label)), lexeme(tag(":")));
+    parse_all(&preceded(header, many0(lexeme(integer::<i64>))), line)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Race {
+    duration: i64,
+    distance: i64,
+}
+
+impl Race {
+    pub fn new(duration: i64, distance: i64) -> Self {
+        Self { duration, distance }
+    }
+
+    pub fn calc_win_limits(&self) -> WinLimits {
+        let race_duration = self.duration;
+        let previous_best_distance = self.distance;
+        let discriminant = race_duration * race_duration - 4 * previous_best_distance;
+
+        // No hold time beats the record, not even holding for half the race
+        if discriminant <= 0 {
+            return WinLimits(1, 0);
+        }
+
+        // Solve quadratic equation
+        let upper_limit_f = (race_duration as f64 + (discriminant as f64).sqrt()) / 2.0;
+
+        // If limit happens to result in equal distance, take the one lower
+        let upper_limit = if upper_limit_f.floor() == upper_limit_f {
+            upper_limit_f.floor() as i64 - 1
+        } else {
+            upper_limit_f.floor() as i64
+        };
+
+        // If limit happens to result in equal distance, take the one higher
+        let lower_limit_f = (race_duration as f64 - (discriminant as f64).sqrt()) / 2.0;
+        let lower_limit = if lower_limit_f.floor() == lower_limit_f {
+            lower_limit_f.ceil() as i64 + 1
+        } else {
+            lower_limit_f.ceil() as i64
+        };
+        WinLimits(lower_limit, upper_limit)
+    }
+
+    pub fn calc_win_combinatios(&self) -> WinCombinations {
+        let limits = self.calc_win_limits();
+        WinCombinations(limits.1 - limits.0 + 1)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WinLimits(i64, i64);
+
+#[derive(Debug, PartialEq)]
+pub struct WinCombinations(i64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{assert_answers, cross_check, read_input};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_example_answers() {
+        assert_answers!(Day06, "example");
+        assert_answers!(Day06, "example2");
+    }
+
+    #[test]
+    fn test_calc_win_limits() {
+        assert_eq!(Race::new(7, 9).calc_win_limits(), WinLimits(2, 5));
+        assert_eq!(Race::new(15, 40).calc_win_limits(), WinLimits(4, 11));
+        assert_eq!(Race::new(30, 200).calc_win_limits(), WinLimits(11, 19));
+        assert_eq!(Race::new(7, 9).calc_win_combinatios(), WinCombinations(4));
+        assert_eq!(Race::new(15, 40).calc_win_combinatios(), WinCombinations(8));
+        assert_eq!(
+            Race::new(30, 200).calc_win_combinatios(),
+            WinCombinations(9)
+        );
+    }
+
+    /// Tries every hold time.
+    fn simulate(&(duration, distance): &(i64, i64)) -> i64 {
+        (0..=duration).filter(|hold| hold * (duration - hold) > distance).count() as i64
+    }
+
+    #[test]
+    fn test_win_combinations_of_small_races() {
+        for duration in 0..40 {
+            for distance in 0..=duration * duration / 4 + 1 {
+                let combinations = Race::new(duration, distance).calc_win_combinatios().0;
+                assert_eq!(combinations, simulate(&(duration, distance)), "{} {}", duration, distance);
+            }
+        }
+    }
+
+    /// A race and a record no larger than one above the best distance of the race.
+    fn races() -> impl Strategy<Value = (i64, i64)> {
+        (0..=1i64 << 12).prop_flat_map(|duration| (Just(duration), 0..=duration * duration / 4 + 1))
+    }
+
+    #[test]
+    fn test_win_combinations_match_every_hold_time() {
+        cross_check(
+            simulate,
+            |&(duration, distance)| Race::new(duration, distance).calc_win_combinatios().0,
+            races(),
+        );
+    }
+
+    #[test]
+    fn test_season() {
+        let test_data = read_input!("example").unwrap();
+        let season = Season::from_str(&test_data).unwrap();
+        println!("{:?}", season);
+        assert_eq!(season.races.len(), 3);
+        assert_eq!(season.races[1], Race::new(15, 40));
+    }
+
+    #[test]
+    fn test_season2() {
+        let test_data = read_input!("example").unwrap();
+        let season = Season::from_str2(&test_data).unwrap();
+        println!("{:?}", season);
+        assert_eq!(season.races.len(), 1);
+        assert_eq!(season.races[0], Race::new(71530, 940200));
+    }
+
+    #[test]
+    fn test_sheet_line_error() {
+        let error = Season::from_str("Time:      7  15   30\nDistance:  9  4x  200").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(error.column(), Some(16));
+    }
+
+    #[test]
+    fn test_missing_distances() {
+        let error = Season::from_str("Time:      7  15   30\nDistance:  9  40").unwrap_err();
+        assert_eq!(error.line(), Some(2));
+        assert!(error.message().contains("Expected 3 distances"), "{}", error);
+        assert!(Season::from_str2("Time:      7  15   30\nDistance:  9  40").is_err());
+        assert!(Season::from_str("Time:      7  15   30").is_err());
+    }
+
+    #[test]
+    fn test_sheet_line() {
+        assert_eq!(sheet_line("Time", "Time:      7  15   30").unwrap(), vec![7, 15, 30]);
+        assert_eq!(sheet_line("Distance", "Distance:  9  40  200").unwrap(), vec![9, 40, 200]);
+        assert!(sheet_line("Distance", "Time:      7  15   30").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use common::{Anonymize, Generate};
+use rand::{
+    rngs::StdRng,
+    seq::{index, SliceRandom},
+    Rng,
+};
+
+use crate::{Day07, HandType};
+
+const FACES: [char; 13] = ['A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+/// `size` hands of five random cards with bids up to 1000.
+impl Generate for Day07 {
+    fn generate(rng: &mut StdRng, size: usize) -> String {
+        (0..size)
+            .map(|_| {
+                let cards: String = (0..5).map(|_| *FACES.choose(rng).unwrap_or(&'A')).collect();
+                format!("{} {}\n", cards, rng.gen_range(1..=1000))
+            })
+            .collect()
+    }
+}
+
+/// The type of every hand and the highest bid.
+#[derive(Debug)]
+pub struct Profile {
+    types: Vec<HandType>,
+    max_bid: i64,
+}
+
+/// As many hands of each type as in the real input, in random order, with random faces and bids.
+impl Anonymize for Day07 {
+    type Profile = Profile;
+
+    fn profile(hands: &Self::Input) -> Profile {
+        Profile {
+            types: hands.hands.iter().map(|hand| hand.score()).collect(),
+            max_bid: hands.hands.iter().map(|hand| hand.bid).max().unwrap_or(1),
+        }
+    }
+
+    fn synthesize(rng: &mut StdRng, profile: &Profile) -> String {
+        let mut types = profile.types.clone();
+        types.shuffle(rng);
+        types
+            .iter()
+            .map(|hand_type| format!("{} {}\n", hand(rng, hand_type), rng.gen_range(1..=profile.max_bid.max(1))))
+            .collect()
+    }
+}
+
+/// Random cards of the given type.
+fn hand(rng: &mut StdRng, hand_type: &HandType) -> String {
+    let groups: &[usize] = match hand_type {
+        HandType::FiveOfAKind => &[5],
+        HandType::FourOfAKind => &[4, 1],
+        HandType::FullHouse => &[3, 2],
+        HandType::ThreeOfAKind => &[3, 1, 1],
+        HandType::TwoPair => &[2, 2, 1],
+        HandType::OnePair => &[2, 1, 1, 1],
+        HandType::HighCard | HandType::Nothing => &[1, 1, 1, 1, 1],
+    };
+    let faces = index::sample(rng, FACES.len(), groups.len());
+    let mut cards: Vec<char> = groups
+        .iter()
+        .zip(faces.iter())
+        .flat_map(|(count, face)| [FACES[face]].repeat(*count))
+        .collect();
+    cards.shuffle(rng);
+    cards.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use common::{samples, Solution};
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+    use strum::VariantArray;
+
+    use super::*;
+    use crate::Hand;
+
+    proptest! {
+        #[test]
+        fn prop_every_hand_has_a_type(sample in samples::<Day07>(1..100)) {
+            let hands = Day07::parse(&sample.input).unwrap();
+            prop_assert_eq!(hands.hands.len(), sample.size);
+            for hand in &hands.hands {
+                prop_assert_ne!(hand.score(), HandType::Nothing, "{:?}", hand);
+            }
+        }
+
+        #[test]
+        fn prop_hand_type_ignores_card_order(sample in samples::<Day07>(1..2)) {
+            let line = sample.input.trim();
+            let (cards, bid) = line.split_once(' ').unwrap();
+            let mut shuffled: Vec<char> = cards.chars().collect();
+            shuffled.shuffle(&mut StdRng::seed_from_u64(sample.seed));
+            let shuffled = format!("{} {}", shuffled.into_iter().collect::<String>(), bid);
+            prop_assert_eq!(Hand::from_str(line).unwrap().score(), Hand::from_str(&shuffled).unwrap().score());
+        }
+
+        #[test]
+        fn prop_anonymized_hands_have_the_same_types(sample in samples::<Day07>(1..100)) {
+            let real = Day07::profile(&Day07::parse(&sample.input).unwrap());
+            let synthetic = Day07::profile(&Day07::parse(&sample.anonymize::<Day07>().unwrap()).unwrap());
+            let count = |types: &[HandType], hand_type: &HandType| types.iter().filter(|t| *t == hand_type).count();
+            for hand_type in HandType::VARIANTS {
+                prop_assert_eq!(count(&synthetic.types, hand_type), count(&real.types, hand_type));
+            }
+            prop_assert!(synthetic.max_bid <= real.max_bid);
+        }
+    }
+}
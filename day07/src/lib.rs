@@ -1,36 +1,31 @@
-use std::{collections::HashMap, path::Path, str::FromStr};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
-use common::{read_test_data, Error};
+use common::{Error, Solution};
 use strum::VariantArray;
 use strum_macros::{EnumString, VariantArray};
-use tracing::info;
-
-fn main() -> Result<(), Error> {
-    tracing_subscriber::fmt()
-        .with_thread_ids(true)
-        .with_target(false)
-        .with_level(true)
-        .with_thread_names(true)
-        .with_line_number(true)
-        .init();
-
-    let pkg_name = env!("CARGO_PKG_NAME");
-    // -------------- Part 1 --------------------
-    info!("****************************************************************");
-    info!("* {}  -  Part 1                                             *", pkg_name);
-    info!("****************************************************************");
-    let test_data = read_test_data(Path::new("./example.dat"))?;
-
-    // -------------- Part 2 --------------------
-    info!("****************************************************************");
-    info!("* {}  -  Part 2                                             *", pkg_name);
-    info!("****************************************************************");
-
-    Ok(())
+
+mod generate;
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Hands;
+
+    fn parse(data: &str) -> Result<Self::Input, Error> {
+        Hands::from_str(data)
+    }
+
+    fn part_one(_hands: &Self::Input) -> Result<impl Display, Error> {
+        Err::<i64, _>(Error::other("Day 07 part 1 is not solved yet"))
+    }
+
+    fn part_two(_hands: &Self::Input) -> Result<impl Display, Error> {
+        Err::<i64, _>(Error::other("Day 07 part 2 is not solved yet"))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, EnumString, VariantArray, Default, Clone)]
-enum HandType {
+pub enum HandType {
     FiveOfAKind,
     FourOfAKind,
     FullHouse,
@@ -54,12 +49,14 @@ impl HandType {
 }
 
 #[derive(Debug, Default)]
-struct Hands {
+pub struct Hands {
     hands: Vec<Hand>,
 }
 
-impl Hands {
-    pub fn from_str(s: &str) -> Result<Self, Error> {
+impl FromStr for Hands {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
         let mut hands = Hands::default();
         for line in s.lines() {
             hands.hands.push(Hand::from_str(line)?);
@@ -69,13 +66,15 @@ impl Hands {
 }
 
 #[derive(Debug, Default, PartialEq)]
-struct Hand {
+pub struct Hand {
     cards: [Card; 5],
     bid: i64,
 }
 
-impl Hand {
-    pub fn from_str(line: &str) -> Result<Self, Error> {
+impl FromStr for Hand {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self, Error> {
         let mut hand = Self::default();
         let mut parts = line.split(" ");
         let cards_str = parts.next().unwrap();
@@ -87,7 +86,9 @@ impl Hand {
         hand.bid = parts.next().unwrap().parse().unwrap();
         Ok(hand)
     }
+}
 
+impl Hand {
     pub fn score(&self) -> HandType {
         let mut card_counts: HashMap<Card, i64> = HashMap::new();
         for i in 0..self.cards.len() {
@@ -133,9 +134,6 @@ impl Hand {
 
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        let score_self = self.score();
-        let score_other = other.score();
-
         if self.cards[0].prio < other.cards[0].prio {
             return Some(std::cmp::Ordering::Less);
         }
@@ -209,8 +207,7 @@ impl CardFace {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use common::read_test_data;
-    use std::path::Path;
+    use common::read_input;
 
     #[test]
     fn test_hand_type() {
@@ -221,7 +218,7 @@ mod tests {
 
     #[test]
     fn test_score() {
-        let test_data = read_test_data(Path::new("./example.dat")).unwrap();
+        let test_data = read_input!("example").unwrap();
         let hands = Hands::from_str(&test_data).unwrap();
         for hand in hands.hands.iter() {
             println!("{:?}", hand.score());
@@ -255,7 +252,7 @@ T2TTT 2
 
     #[test]
     fn test_hands_2() {
-        let test_data = read_test_data(Path::new("./example.dat")).unwrap();
+        let test_data = read_input!("example").unwrap();
         let hands = Hands::from_str(&test_data).unwrap();
         assert!(hands.hands[0] < hands.hands[1]);
         assert!(hands.hands[0] <= hands.hands[0]);
@@ -265,7 +262,7 @@ T2TTT 2
 
     #[test]
     fn test_hands_1() {
-        let test_data = read_test_data(Path::new("./example.dat")).unwrap();
+        let test_data = read_input!("example").unwrap();
         let hands = Hands::from_str(&test_data).unwrap();
         for (i, hand) in hands.hands.iter().enumerate() {
             println!("{:?}", hand);